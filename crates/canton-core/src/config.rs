@@ -21,21 +21,84 @@ pub struct SdkConfig {
 }
 
 impl SdkConfig {
-    /// Load from default paths (e.g. config.yaml, environment).
+    pub fn builder() -> SdkConfigBuilder {
+        SdkConfigBuilder::default()
+    }
+
+    /// Load from default paths (e.g. config.yaml, environment), then apply
+    /// [`apply_env_overrides`](Self::apply_env_overrides).
     pub fn load() -> SdkResult<Self> {
         let path = std::env::var("CANTON_SDK_CONFIG").unwrap_or_else(|_| "config.yaml".to_string());
-        Self::load_from_file(&path)
+        // Validated once, after overrides, so env vars can fix what the file gets wrong.
+        let mut config = Self::parse_file(&path)?;
+        config.apply_env_overrides()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Override fields from environment variables; unset variables leave the field untouched,
+    /// malformed values are a [`SdkError::Config`].
+    ///
+    /// | Variable | Field |
+    /// |---|---|
+    /// | `CANTON_LEDGER_ENDPOINT` | `canton.endpoint` |
+    /// | `CANTON_CONNECT_TIMEOUT_SECS` | `canton.connect_timeout_secs` |
+    /// | `CANTON_REQUEST_TIMEOUT_SECS` | `canton.request_timeout_secs` |
+    /// | `CANTON_POOL_MAX_SIZE` | `canton.pool.max_size` |
+    /// | `CANTON_LOG_LEVEL` | `observability.logging_level` |
+    pub fn apply_env_overrides(&mut self) -> SdkResult<()> {
+        // Everything is read and validated before anything is assigned, so an error leaves
+        // `self` untouched.
+        let endpoint = env_var("CANTON_LEDGER_ENDPOINT")?;
+        if let Some(endpoint) = &endpoint {
+            validate_endpoint_url(endpoint)
+                .map_err(|e| SdkError::Config(format!("CANTON_LEDGER_ENDPOINT: {}", e)))?;
+        }
+        let connect_timeout_secs = env_parse("CANTON_CONNECT_TIMEOUT_SECS")?;
+        let request_timeout_secs = env_parse("CANTON_REQUEST_TIMEOUT_SECS")?;
+        let pool_max_size = env_parse("CANTON_POOL_MAX_SIZE")?;
+        let logging_level = env_var("CANTON_LOG_LEVEL")?;
+
+        if let Some(endpoint) = endpoint {
+            self.canton.endpoint = endpoint;
+        }
+        if connect_timeout_secs.is_some() {
+            self.canton.connect_timeout_secs = connect_timeout_secs;
+        }
+        if request_timeout_secs.is_some() {
+            self.canton.request_timeout_secs = request_timeout_secs;
+        }
+        if let Some(max_size) = pool_max_size {
+            self.canton.pool.max_size = max_size;
+        }
+        if logging_level.is_some() {
+            self.observability.logging_level = logging_level;
+        }
+        Ok(())
     }
 
-    /// Load from file.
+    /// Load from file: JSON for a `.json` extension, YAML otherwise.
     pub fn load_from_file(path: impl AsRef<Path>) -> SdkResult<Self> {
-        let content = std::fs::read_to_string(path.as_ref())
-            .map_err(|e| SdkError::Config(e.to_string()))?;
-        let config: Self = serde_yaml::from_str(&content).map_err(|e| SdkError::Config(e.to_string()))?;
+        let config = Self::parse_file(path)?;
         config.validate()?;
         Ok(config)
     }
 
+    fn parse_file(path: impl AsRef<Path>) -> SdkResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| SdkError::Config(format!("failed to read {}: {}", path.display(), e)))?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let config: Self = if is_json {
+            serde_json::from_str(&content).map_err(|e| SdkError::Config(format!("{}: {}", path.display(), e)))?
+        } else {
+            serde_yaml::from_str(&content).map_err(|e| SdkError::Config(format!("{}: {}", path.display(), e)))?
+        };
+        Ok(config)
+    }
+
     /// Validate configuration.
     pub fn validate(&self) -> SdkResult<()> {
         self.canton.validate()?;
@@ -50,6 +113,144 @@ impl SdkConfig {
     }
 }
 
+/// Fluent construction of [`SdkConfig`]; `build` runs the same validation as file loading.
+#[derive(Debug, Clone, Default)]
+pub struct SdkConfigBuilder {
+    endpoint: Option<String>,
+    tls: Option<TlsConfig>,
+    connect_timeout_secs: Option<u64>,
+    request_timeout_secs: Option<u64>,
+    keep_alive_interval_secs: Option<u64>,
+    canton_reliability: CantonReliabilityConfig,
+    pool: ConnectionPoolConfig,
+    reliability: ReliabilityConfig,
+    observability: ObservabilityConfig,
+    omnichain: Option<OmniChainConfig>,
+}
+
+impl SdkConfigBuilder {
+    pub fn ledger_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    pub fn tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Rounded up to whole seconds, so a sub-second timeout never becomes zero.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout_secs = Some(whole_secs_ceil(timeout));
+        self
+    }
+
+    /// Rounded up to whole seconds, like [`Self::connect_timeout`].
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout_secs = Some(whole_secs_ceil(timeout));
+        self
+    }
+
+    /// Rounded up to whole seconds, like [`Self::connect_timeout`].
+    pub fn keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.keep_alive_interval_secs = Some(whole_secs_ceil(interval));
+        self
+    }
+
+    /// Reliability settings of the ledger connection (`canton.reliability`); the SDK-wide
+    /// ones are set with [`Self::with_circuit_breaker`] and friends.
+    pub fn canton_reliability(mut self, config: CantonReliabilityConfig) -> Self {
+        self.canton_reliability = config;
+        self
+    }
+
+    pub fn pool(mut self, pool: ConnectionPoolConfig) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.reliability.circuit_breaker = Some(config);
+        self
+    }
+
+    pub fn with_rate_limiter(mut self, config: RateLimiterConfig) -> Self {
+        self.reliability.rate_limiter = Some(config);
+        self
+    }
+
+    pub fn with_retry(mut self, config: RetryConfig) -> Self {
+        self.reliability.retry = Some(config);
+        self
+    }
+
+    pub fn observability(mut self, config: ObservabilityConfig) -> Self {
+        self.observability = config;
+        self
+    }
+
+    /// Enable an OmniChain chain; its [`ChainConfig`] must be supplied via [`chain`](Self::chain).
+    pub fn enable_chain(mut self, chain_id: impl Into<String>) -> Self {
+        self.omnichain_mut().enabled_chains.push(chain_id.into());
+        self
+    }
+
+    pub fn chain(mut self, chain_id: impl Into<String>, config: ChainConfig) -> Self {
+        self.omnichain_mut().chains.insert(chain_id.into(), config);
+        self
+    }
+
+    fn omnichain_mut(&mut self) -> &mut OmniChainConfig {
+        self.omnichain.get_or_insert_with(|| OmniChainConfig {
+            enabled_chains: Vec::new(),
+            chains: HashMap::new(),
+        })
+    }
+
+    pub fn build(self) -> SdkResult<SdkConfig> {
+        let endpoint = self
+            .endpoint
+            .ok_or_else(|| SdkError::Config("canton.endpoint must be set".into()))?;
+        let config = SdkConfig {
+            canton: CantonConfig {
+                endpoint,
+                tls: self.tls,
+                connect_timeout_secs: self.connect_timeout_secs,
+                request_timeout_secs: self.request_timeout_secs,
+                keep_alive_interval_secs: self.keep_alive_interval_secs,
+                reliability: self.canton_reliability,
+                pool: self.pool,
+            },
+            reliability: self.reliability,
+            observability: self.observability,
+            omnichain: self.omnichain,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+fn whole_secs_ceil(duration: Duration) -> u64 {
+    duration.as_secs().saturating_add(u64::from(duration.subsec_nanos() > 0))
+}
+
+fn env_var(name: &str) -> SdkResult<Option<String>> {
+    match std::env::var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(e) => Err(SdkError::Config(format!("{}: {}", name, e))),
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(name: &str) -> SdkResult<Option<T>>
+where
+    T::Err: std::fmt::Display,
+{
+    env_var(name)?
+        .map(|v| v.trim().parse().map_err(|e| SdkError::Config(format!("{}={:?}: {}", name, v, e))))
+        .transpose()
+}
+
 /// Canton ledger connection config.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CantonConfig {
@@ -64,6 +265,8 @@ pub struct CantonConfig {
     pub keep_alive_interval_secs: Option<u64>,
     #[serde(default)]
     pub reliability: CantonReliabilityConfig,
+    #[serde(default)]
+    pub pool: ConnectionPoolConfig,
 }
 
 impl CantonConfig {
@@ -71,6 +274,11 @@ impl CantonConfig {
         if self.endpoint.is_empty() {
             return Err(SdkError::Config("canton.endpoint must be set".into()));
         }
+        validate_endpoint_url(&self.endpoint)?;
+        if let Some(ref tls) = self.tls {
+            tls.validate()?;
+        }
+        self.pool.validate()?;
         Ok(())
     }
 
@@ -93,6 +301,29 @@ impl CantonConfig {
     }
 }
 
+/// `http(s)://host[:port][/path]`, as accepted by the gRPC transport.
+fn validate_endpoint_url(endpoint: &str) -> SdkResult<()> {
+    let invalid = |reason: &str| SdkError::Config(format!("canton.endpoint {:?} is not a valid URL: {}", endpoint, reason));
+    let rest = endpoint
+        .strip_prefix("https://")
+        .or_else(|| endpoint.strip_prefix("http://"))
+        .ok_or_else(|| invalid("scheme must be http or https"))?;
+    let authority = rest.split('/').next().unwrap_or_default();
+    // Bracketed IPv6 literals contain colons of their own.
+    let port_sep = authority.rfind(':').filter(|&i| authority.rfind(']').map_or(true, |b| i > b));
+    let (host, port) = match port_sep {
+        Some(i) => (&authority[..i], Some(&authority[i + 1..])),
+        None => (authority, None),
+    };
+    if host.is_empty() || host.chars().any(|c| c.is_whitespace() || c == '@') {
+        return Err(invalid("missing or malformed host"));
+    }
+    if let Some(port) = port {
+        port.parse::<u16>().map_err(|_| invalid("port must be a number between 0 and 65535"))?;
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CantonReliabilityConfig {
     #[serde(default)]
@@ -103,7 +334,47 @@ pub struct CantonReliabilityConfig {
     pub retry: Option<RetryConfig>,
 }
 
-/// TLS config.
+/// Connection pool sizing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionPoolConfig {
+    #[serde(default = "default_pool_max_size")]
+    pub max_size: usize,
+    /// Idle connections the pool keeps ready; dead ones are recreated on maintenance.
+    #[serde(default)]
+    pub min_idle: usize,
+    /// Establish `min_idle` connections when the pool is created instead of on first use.
+    #[serde(default)]
+    pub eager_init: bool,
+}
+
+impl Default for ConnectionPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: default_pool_max_size(),
+            min_idle: 0,
+            eager_init: false,
+        }
+    }
+}
+
+impl ConnectionPoolConfig {
+    pub fn validate(&self) -> SdkResult<()> {
+        if self.max_size == 0 {
+            return Err(SdkError::Config("canton.pool.max_size must be at least 1".into()));
+        }
+        if self.min_idle > self.max_size {
+            return Err(SdkError::Config(format!(
+                "canton.pool.min_idle ({}) exceeds max_size ({})",
+                self.min_idle, self.max_size
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn default_pool_max_size() -> usize { 10 }
+
+/// TLS config. Setting `client_cert_path` and `client_key_path` enables mutual TLS.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlsConfig {
     pub ca_cert_path: Option<String>,
@@ -111,6 +382,26 @@ pub struct TlsConfig {
     pub client_key_path: Option<String>,
 }
 
+impl TlsConfig {
+    /// The client certificate and key must be set together or not at all.
+    pub fn validate(&self) -> SdkResult<()> {
+        match (&self.client_cert_path, &self.client_key_path) {
+            (Some(_), None) => Err(SdkError::Config(
+                "canton.tls.client_cert_path is set but client_key_path is missing".into(),
+            )),
+            (None, Some(_)) => Err(SdkError::Config(
+                "canton.tls.client_key_path is set but client_cert_path is missing".into(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether a client identity (mTLS) is configured.
+    pub fn is_mutual(&self) -> bool {
+        self.client_cert_path.is_some() && self.client_key_path.is_some()
+    }
+}
+
 /// Reliability (circuit breaker, retry) config.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ReliabilityConfig {
@@ -142,12 +433,25 @@ fn default_reset_timeout_secs() -> u64 { 30 }
 fn default_window_duration_secs() -> u64 { 60 }
 fn default_half_open_max_requests() -> u32 { 3 }
 
+/// Default limit per key; `operations` overrides it for named operations, each with its own
+/// bucket. `strategy` is `token_bucket` (default when empty) or `fixed_window`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimiterConfig {
     pub max_requests: u64,
     pub window_secs: u64,
     #[serde(default)]
     pub strategy: String,
+    #[serde(default)]
+    pub operations: HashMap<String, OperationRateLimit>,
+    /// Aggregate ceiling across all keys and operations, on top of the per-key limits.
+    #[serde(default)]
+    pub global_max_per_sec: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationRateLimit {
+    pub max_requests: u64,
+    pub window_secs: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -159,6 +463,9 @@ pub struct RetryConfig {
     pub multiplier: f64,
     #[serde(default = "default_true")]
     pub jitter: bool,
+    /// Overall budget across attempts and backoff sleeps; no retry starts past it.
+    #[serde(default)]
+    pub total_deadline_ms: Option<u64>,
 }
 
 fn default_true() -> bool { true }
@@ -169,6 +476,9 @@ pub struct ObservabilityConfig {
     pub logging_level: Option<String>,
     pub logging_format: Option<String>,
     pub metrics_endpoint: Option<String>,
+    /// Cap on distinct label sets per metric; further ones are folded into an overflow series.
+    #[serde(default)]
+    pub metrics_cardinality_limit: Option<usize>,
     pub tracing_endpoint: Option<String>,
     pub tracing_sample_rate: Option<f64>,
 }
@@ -185,3 +495,161 @@ pub struct ChainConfig {
     pub rpc_url: String,
     pub chain_id: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("canton-sdk-{}-{}", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_json_and_yaml_by_extension() {
+        let json = write_temp(
+            "config.json",
+            r#"{"canton": {"endpoint": "https://canton.example.com:6865", "request_timeout_secs": 5}}"#,
+        );
+        let yaml = write_temp(
+            "config.yaml",
+            "canton:\n  endpoint: http://localhost:6865\n  pool:\n    max_size: 4\n",
+        );
+
+        let from_json = SdkConfig::load_from_file(&json).unwrap();
+        assert_eq!(from_json.canton.endpoint, "https://canton.example.com:6865");
+        assert_eq!(from_json.canton.request_timeout(), Duration::from_secs(5));
+
+        let from_yaml = SdkConfig::load_from_file(&yaml).unwrap();
+        assert_eq!(from_yaml.canton.endpoint, "http://localhost:6865");
+        assert_eq!(from_yaml.canton.pool.max_size, 4);
+
+        let _ = std::fs::remove_file(json);
+        let _ = std::fs::remove_file(yaml);
+    }
+
+    #[test]
+    fn env_overrides_apply_and_reject_malformed_values() {
+        let mut config: SdkConfig =
+            serde_json::from_str(r#"{"canton": {"endpoint": "https://a.example.com", "connect_timeout_secs": 3}}"#).unwrap();
+        std::env::set_var("CANTON_LEDGER_ENDPOINT", "http://b.example.com:6865");
+        std::env::set_var("CANTON_REQUEST_TIMEOUT_SECS", "7");
+        std::env::set_var("CANTON_LOG_LEVEL", "debug");
+        config.apply_env_overrides().unwrap();
+        assert_eq!(config.canton.endpoint, "http://b.example.com:6865");
+        assert_eq!(config.canton.request_timeout_secs, Some(7));
+        assert_eq!(config.canton.connect_timeout_secs, Some(3));
+        assert_eq!(config.observability.logging_level.as_deref(), Some("debug"));
+
+        // A file whose endpoint only the override makes valid still loads.
+        let file = write_temp("override.json", r#"{"canton": {"endpoint": "https://"}}"#);
+        std::env::set_var("CANTON_SDK_CONFIG", &file);
+        assert_eq!(SdkConfig::load().unwrap().canton.endpoint, "http://b.example.com:6865");
+        assert!(SdkConfig::load_from_file(&file).is_err());
+        std::env::remove_var("CANTON_SDK_CONFIG");
+        let _ = std::fs::remove_file(file);
+
+        // A malformed variable rejects the whole batch, including the valid ones read before it.
+        std::env::set_var("CANTON_LEDGER_ENDPOINT", "http://c.example.com:6865");
+        std::env::set_var("CANTON_CONNECT_TIMEOUT_SECS", "9");
+        std::env::set_var("CANTON_REQUEST_TIMEOUT_SECS", "11");
+        std::env::set_var("CANTON_POOL_MAX_SIZE", "many");
+        assert!(matches!(config.apply_env_overrides(), Err(SdkError::Config(_))));
+        assert_eq!(config.canton.endpoint, "http://b.example.com:6865");
+        assert_eq!(config.canton.connect_timeout_secs, Some(3));
+        assert_eq!(config.canton.request_timeout_secs, Some(7));
+        assert_eq!(config.canton.pool.max_size, 10);
+
+        for name in [
+            "CANTON_LEDGER_ENDPOINT",
+            "CANTON_CONNECT_TIMEOUT_SECS",
+            "CANTON_REQUEST_TIMEOUT_SECS",
+            "CANTON_LOG_LEVEL",
+            "CANTON_POOL_MAX_SIZE",
+        ] {
+            std::env::remove_var(name);
+        }
+    }
+
+    #[test]
+    fn builder_validates_chain_dependencies() {
+        let config = SdkConfig::builder()
+            .ledger_endpoint("https://canton.example.com:6865")
+            .request_timeout(Duration::from_secs(12))
+            .with_retry(RetryConfig {
+                max_attempts: 3,
+                initial_delay_ms: 100,
+                max_delay_ms: 1000,
+                multiplier: 2.0,
+                jitter: true,
+                total_deadline_ms: None,
+            })
+            .enable_chain("ethereum")
+            .chain("ethereum", ChainConfig {
+                rpc_url: "https://rpc.example.com".into(),
+                chain_id: "1".into(),
+            })
+            .build()
+            .unwrap();
+        assert_eq!(config.canton.request_timeout(), Duration::from_secs(12));
+        assert_eq!(config.reliability.retry.unwrap().max_attempts, 3);
+        assert_eq!(config.omnichain.unwrap().enabled_chains, vec!["ethereum"]);
+
+        let missing_chain = SdkConfig::builder()
+            .ledger_endpoint("https://canton.example.com:6865")
+            .enable_chain("cosmos")
+            .build();
+        assert!(matches!(missing_chain, Err(SdkError::Config(m)) if m.contains("cosmos")));
+        assert!(SdkConfig::builder().build().is_err());
+    }
+
+    #[test]
+    fn builder_rounds_sub_second_timeouts_up() {
+        let config = SdkConfig::builder()
+            .ledger_endpoint("https://canton.example.com:6865")
+            .connect_timeout(Duration::from_millis(500))
+            .request_timeout(Duration::from_millis(2500))
+            .keep_alive_interval(Duration::from_millis(100))
+            .canton_reliability(CantonReliabilityConfig {
+                retry: Some(RetryConfig {
+                    max_attempts: 5,
+                    initial_delay_ms: 50,
+                    max_delay_ms: 500,
+                    multiplier: 2.0,
+                    jitter: false,
+                    total_deadline_ms: None,
+                }),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        assert_eq!(config.canton.connect_timeout(), Duration::from_secs(1));
+        assert_eq!(config.canton.request_timeout(), Duration::from_secs(3));
+        assert_eq!(config.canton.keep_alive_interval(), Duration::from_secs(1));
+        assert!(config.canton.reliability.retry.is_some());
+
+        let saturated = SdkConfig::builder()
+            .ledger_endpoint("https://canton.example.com:6865")
+            .request_timeout(Duration::MAX)
+            .build()
+            .unwrap();
+        assert_eq!(saturated.canton.request_timeout_secs, Some(u64::MAX));
+    }
+
+    #[test]
+    fn rejects_unparseable_endpoint() {
+        for endpoint in [
+            "localhost:6865",
+            "ftp://host",
+            "https://",
+            "https://host:port",
+            "https://[::1]:x",
+            "http://:6865",
+        ] {
+            let config: CantonConfig = serde_json::from_str(&format!(r#"{{"endpoint": "{}"}}"#, endpoint)).unwrap();
+            assert!(matches!(config.validate(), Err(SdkError::Config(_))), "{}", endpoint);
+        }
+        assert!(validate_endpoint_url("https://[::1]:6865/ledger").is_ok());
+    }
+}
@@ -4,6 +4,8 @@
 use std::backtrace::Backtrace;
 use std::collections::HashMap;
 
+use crate::types::completion::CompletionStatus;
+
 /// Main SDK error type.
 #[derive(Debug)]
 pub enum SdkError {
@@ -44,6 +46,9 @@ pub enum SdkError {
 
     CircuitOpen,
 
+    /// Signing requested from a wallet that holds no keys (watch-only).
+    WatchOnly { party: String },
+
     CrossChain {
         message: String,
         source_chain: Option<String>,
@@ -62,7 +67,20 @@ impl std::fmt::Display for SdkError {
         match self {
             SdkError::Connection { message, .. } => write!(f, "Connection error: {}", message),
             SdkError::Authentication { reason, .. } => write!(f, "Authentication failed: {}", reason),
-            SdkError::Transaction { kind, .. } => write!(f, "Transaction error: {}", kind),
+            SdkError::Transaction { kind, details, .. } => {
+                write!(f, "Transaction error: {}", kind)?;
+                if let Some(code) = details.get(DETAIL_ERROR_CODE) {
+                    write!(f, " [{}", code)?;
+                    if let Some(id) = details.get(DETAIL_CORRELATION_ID) {
+                        write!(f, ", correlation id {}", id)?;
+                    }
+                    f.write_str("]")?;
+                }
+                if let Some(message) = details.get(DETAIL_MESSAGE) {
+                    write!(f, ": {}", message)?;
+                }
+                Ok(())
+            }
             SdkError::Validation { field, message } => write!(f, "Validation error: {} - {}", field, message),
             SdkError::Config(s) => write!(f, "Configuration error: {}", s),
             SdkError::Serialization(s) => write!(f, "Serialization error: {}", s),
@@ -72,6 +90,9 @@ impl std::fmt::Display for SdkError {
             }
             SdkError::RateLimited { retry_after } => write!(f, "Rate limited, retry after {:?}", retry_after),
             SdkError::CircuitOpen => write!(f, "Circuit breaker open"),
+            SdkError::WatchOnly { party } => {
+                write!(f, "Wallet for {} is watch-only; signing is not available", party)
+            }
             SdkError::CrossChain { message, .. } => write!(f, "Cross-chain error: {}", message),
             SdkError::Internal { message, .. } => write!(f, "Internal error: {}", message),
         }
@@ -94,6 +115,10 @@ impl std::error::Error for SdkError {
 pub enum TransactionErrorKind {
     InvalidCommand,
     ContractNotFound,
+    ContractKeyNotFound,
+    DuplicateContractKey,
+    /// Input contract archived, or locked by a concurrent transaction.
+    ContractNotActive,
     ChoiceNotFound,
     AuthorizationFailed,
     Conflict,
@@ -107,6 +132,9 @@ impl std::fmt::Display for TransactionErrorKind {
         f.write_str(match self {
             Self::InvalidCommand => "InvalidCommand",
             Self::ContractNotFound => "ContractNotFound",
+            Self::ContractKeyNotFound => "ContractKeyNotFound",
+            Self::DuplicateContractKey => "DuplicateContractKey",
+            Self::ContractNotActive => "ContractNotActive",
             Self::ChoiceNotFound => "ChoiceNotFound",
             Self::AuthorizationFailed => "AuthorizationFailed",
             Self::Conflict => "Conflict",
@@ -117,7 +145,145 @@ impl std::fmt::Display for TransactionErrorKind {
     }
 }
 
+impl From<&CompletionStatus> for TransactionErrorKind {
+    fn from(status: &CompletionStatus) -> Self {
+        match status {
+            CompletionStatus::Succeeded => Self::Unknown,
+            CompletionStatus::ContractNotFound { .. } => Self::ContractNotFound,
+            CompletionStatus::ContractKeyNotFound { .. } => Self::ContractKeyNotFound,
+            CompletionStatus::DuplicateContractKey { .. } => Self::DuplicateContractKey,
+            CompletionStatus::ContractNotActive { .. } => Self::ContractNotActive,
+            CompletionStatus::DuplicateCommand { .. } => Self::Rejected,
+            CompletionStatus::AuthorizationFailed { .. } => Self::AuthorizationFailed,
+            CompletionStatus::InvalidCommand { .. } => Self::InvalidCommand,
+            CompletionStatus::Timeout { .. } => Self::Timeout,
+            CompletionStatus::SubmissionErrorUnknown { .. } => Self::Unknown,
+        }
+    }
+}
+
+/// `details` keys under which [`SdkError::Transaction`] carries a parsed [`CantonErrorCode`].
+pub const DETAIL_ERROR_CODE: &str = "error_code";
+pub const DETAIL_ERROR_CATEGORY: &str = "error_category";
+pub const DETAIL_CORRELATION_ID: &str = "correlation_id";
+pub const DETAIL_MESSAGE: &str = "message";
+/// Offending contract id, when the rejection message names one.
+pub const DETAIL_CONTRACT_ID: &str = "contract_id";
+
+/// Canton error in the standard `<CODE>(<category>,<correlation-id>): <message>` format,
+/// e.g. `CONTRACT_NOT_FOUND(11,4f2a9c1d): Contract could not be found with id 00abc`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CantonErrorCode {
+    pub code: String,
+    pub category: u32,
+    pub correlation_id: String,
+    pub message: String,
+}
+
+impl CantonErrorCode {
+    /// Parse a Canton error string; `None` if it does not follow the standard format.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim_start();
+        let open = s.find('(')?;
+        let code = &s[..open];
+        if code.is_empty()
+            || !code
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        {
+            return None;
+        }
+        let close = open + s[open..].find(')')?;
+        let (category, correlation_id) = s[open + 1..close].split_once(',')?;
+        let rest = &s[close + 1..];
+        let message = rest.strip_prefix(':').unwrap_or(rest).trim_start();
+        Some(Self {
+            code: code.to_string(),
+            category: category.trim().parse().ok()?,
+            correlation_id: correlation_id.trim().to_string(),
+            message: message.to_string(),
+        })
+    }
+
+    /// `Transaction` error of `kind` carrying this code in its `details`.
+    pub fn into_error(self, kind: TransactionErrorKind) -> SdkError {
+        let mut details = HashMap::new();
+        details.insert(DETAIL_ERROR_CODE.to_string(), self.code);
+        details.insert(DETAIL_ERROR_CATEGORY.to_string(), self.category.to_string());
+        details.insert(DETAIL_CORRELATION_ID.to_string(), self.correlation_id);
+        details.insert(DETAIL_MESSAGE.to_string(), self.message);
+        SdkError::Transaction {
+            kind,
+            transaction_id: None,
+            details,
+            cause: None,
+        }
+    }
+}
+
+/// First token that looks like a Canton contract id (`00` + at least 64 hex digits).
+fn contract_id_in(message: &str) -> Option<&str> {
+    message
+        .split(|c: char| !c.is_ascii_hexdigit())
+        .find(|t| t.len() >= 66 && t.starts_with("00"))
+}
+
+impl std::fmt::Display for CantonErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({},{}): {}", self.code, self.category, self.correlation_id, self.message)
+    }
+}
+
 impl SdkError {
+    /// Transaction error for a failed completion; `None` for success. The Canton error code
+    /// and the offending contract id (if the message names one) are kept in `details`.
+    pub fn from_completion_status(status: &CompletionStatus) -> Option<SdkError> {
+        if status.is_success() {
+            return None;
+        }
+        let kind = TransactionErrorKind::from(status);
+        let mut error = match CantonErrorCode::parse(status.message()) {
+            Some(code) => code.into_error(kind),
+            None => SdkError::Transaction {
+                kind,
+                transaction_id: None,
+                details: HashMap::from([(DETAIL_MESSAGE.to_string(), status.message().to_string())]),
+                cause: None,
+            },
+        };
+        if let (SdkError::Transaction { details, .. }, Some(id)) = (&mut error, contract_id_in(status.message())) {
+            details.insert(DETAIL_CONTRACT_ID.to_string(), id.to_string());
+        }
+        Some(error)
+    }
+
+    /// Offending contract id of a transaction error, if known.
+    pub fn contract_id(&self) -> Option<&str> {
+        self.detail(DETAIL_CONTRACT_ID)
+    }
+
+    /// Canton error-code id (e.g. `CONTRACT_NOT_FOUND`) of a transaction error, if known.
+    pub fn code(&self) -> Option<&str> {
+        self.detail(DETAIL_ERROR_CODE)
+    }
+
+    /// Canton error category number of a transaction error, if known.
+    pub fn category(&self) -> Option<u32> {
+        self.detail(DETAIL_ERROR_CATEGORY)?.parse().ok()
+    }
+
+    /// Correlation id to quote when looking the failure up in participant logs.
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.detail(DETAIL_CORRELATION_ID)
+    }
+
+    fn detail(&self, key: &str) -> Option<&str> {
+        match self {
+            SdkError::Transaction { details, .. } => details.get(key).map(String::as_str),
+            _ => None,
+        }
+    }
+
     /// Whether the error is retryable.
     pub fn is_retryable(&self) -> bool {
         matches!(
@@ -145,6 +311,7 @@ impl SdkError {
             SdkError::Timeout { .. } => "timeout",
             SdkError::RateLimited { .. } => "rate_limited",
             SdkError::CircuitOpen => "circuit_open",
+            SdkError::WatchOnly { .. } => "watch_only",
             SdkError::CrossChain { .. } => "cross_chain",
             SdkError::Internal { .. } => "internal",
         }
@@ -153,3 +320,58 @@ impl SdkError {
 
 /// Result alias.
 pub type SdkResult<T> = Result<T, SdkError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_canton_error_code_into_transaction_error() {
+        let raw = "CONTRACT_NOT_FOUND(11,4f2a9c1d8e3b7a60): Contract could not be found with id 00e1f2a3b4";
+        let parsed = CantonErrorCode::parse(raw).expect("standard format");
+        assert_eq!(parsed.to_string(), raw);
+
+        let err = parsed.into_error(TransactionErrorKind::ContractNotFound);
+        assert_eq!(err.code(), Some("CONTRACT_NOT_FOUND"));
+        assert_eq!(err.category(), Some(11));
+        assert_eq!(err.correlation_id(), Some("4f2a9c1d8e3b7a60"));
+        assert_eq!(
+            err.to_string(),
+            "Transaction error: ContractNotFound [CONTRACT_NOT_FOUND, correlation id 4f2a9c1d8e3b7a60]: \
+             Contract could not be found with id 00e1f2a3b4"
+        );
+    }
+
+    #[test]
+    fn completion_status_maps_to_rejection_kind() {
+        let cid = format!("00{}", "ab".repeat(33));
+        let not_found = CompletionStatus::from_grpc(
+            5,
+            &format!("CONTRACT_NOT_FOUND(11,c0ffee): Contract could not be found with id {}", cid),
+        );
+        let err = SdkError::from_completion_status(&not_found).unwrap();
+        assert!(matches!(err, SdkError::Transaction { kind: TransactionErrorKind::ContractNotFound, .. }));
+        assert_eq!(err.contract_id(), Some(cid.as_str()));
+        assert_eq!(err.code(), Some("CONTRACT_NOT_FOUND"));
+
+        let cases = [
+            ("CONTRACT_KEY_NOT_FOUND(11,1): key not found", TransactionErrorKind::ContractKeyNotFound),
+            ("DUPLICATE_CONTRACT_KEY(4,2): key exists", TransactionErrorKind::DuplicateContractKey),
+            ("LOCAL_VERDICT_INACTIVE_CONTRACTS(4,3): archived", TransactionErrorKind::ContractNotActive),
+            ("DAML_AUTHORIZATION_ERROR(8,4): missing authorizer", TransactionErrorKind::AuthorizationFailed),
+        ];
+        for (message, kind) in cases {
+            let status = CompletionStatus::from_grpc(9, message);
+            assert_eq!(TransactionErrorKind::from(&status), kind, "{}", message);
+            assert!(!SdkError::from_completion_status(&status).unwrap().is_retryable());
+        }
+        assert!(SdkError::from_completion_status(&CompletionStatus::Succeeded).is_none());
+    }
+
+    #[test]
+    fn non_canton_messages_do_not_parse() {
+        assert!(CantonErrorCode::parse("connection reset by peer").is_none());
+        assert!(CantonErrorCode::parse("Bad(request): nope").is_none());
+        assert_eq!(SdkError::Config("x".into()).code(), None);
+    }
+}
@@ -9,7 +9,7 @@ pub mod types;
 
 pub mod traits;
 
-pub use error::{SdkError, SdkResult, TransactionErrorKind};
+pub use error::{CantonErrorCode, SdkError, SdkResult, TransactionErrorKind};
 pub use config::*;
 pub use types::*;
 pub use traits::*;
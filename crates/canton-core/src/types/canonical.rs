@@ -0,0 +1,323 @@
+//! Deterministic byte encoding of commands and values, for signing.
+//!
+//! Every item is a one-byte tag followed by its payload; strings and byte strings are
+//! length-prefixed (u32 big-endian), integers big-endian. Set-like data (`act_as`, `read_as`,
+//! `TextMap` entries) is sorted first, so the encoding does not depend on insertion order.
+//! Record fields, lists and `GenMap` entries keep their order, which is significant in Daml.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::types::command::{Command, Commands};
+use crate::types::identifier::Identifier;
+use crate::types::value::{DamlRecord, DamlValue};
+
+/// Version prefix of [`Commands::canonical_bytes`]; bump if the layout ever changes.
+pub const CANONICAL_COMMANDS_V1: &[u8] = b"canton-sdk/commands/v1";
+
+#[derive(Default)]
+struct Encoder {
+    out: Vec<u8>,
+}
+
+impl Encoder {
+    fn tag(&mut self, tag: u8) {
+        self.out.push(tag);
+    }
+
+    fn bytes(&mut self, b: &[u8]) {
+        self.out.extend_from_slice(&(b.len() as u32).to_be_bytes());
+        self.out.extend_from_slice(b);
+    }
+
+    fn str(&mut self, s: &str) {
+        self.bytes(s.as_bytes());
+    }
+
+    fn len(&mut self, n: usize) {
+        self.out.extend_from_slice(&(n as u32).to_be_bytes());
+    }
+
+    fn opt_str(&mut self, s: Option<&str>) {
+        match s {
+            Some(s) => {
+                self.tag(1);
+                self.str(s);
+            }
+            None => self.tag(0),
+        }
+    }
+
+    fn sorted_strs(&mut self, items: &[String]) {
+        let mut sorted: Vec<&String> = items.iter().collect();
+        sorted.sort();
+        self.len(sorted.len());
+        for s in sorted {
+            self.str(s);
+        }
+    }
+
+    fn timestamp(&mut self, t: Option<&DateTime<Utc>>) {
+        match t {
+            Some(t) => {
+                self.tag(1);
+                self.out.extend_from_slice(&t.timestamp_micros().to_be_bytes());
+            }
+            None => self.tag(0),
+        }
+    }
+
+    fn duration(&mut self, d: Option<&Duration>) {
+        match d {
+            Some(d) => {
+                self.tag(1);
+                self.out.extend_from_slice(&d.as_secs().to_be_bytes());
+                self.out.extend_from_slice(&d.subsec_nanos().to_be_bytes());
+            }
+            None => self.tag(0),
+        }
+    }
+
+    fn identifier(&mut self, id: &Identifier) {
+        self.str(&id.package_id);
+        self.str(&id.module_name);
+        self.str(&id.entity_name);
+    }
+
+    fn opt_identifier(&mut self, id: Option<&Identifier>) {
+        match id {
+            Some(id) => {
+                self.tag(1);
+                self.identifier(id);
+            }
+            None => self.tag(0),
+        }
+    }
+
+    fn record(&mut self, record: &DamlRecord) {
+        self.opt_identifier(record.record_id.as_ref());
+        self.len(record.fields.len());
+        for field in &record.fields {
+            self.str(&field.label);
+            self.value(&field.value);
+        }
+    }
+
+    fn value(&mut self, value: &DamlValue) {
+        match value {
+            DamlValue::Unit => self.tag(0x00),
+            DamlValue::Bool(b) => {
+                self.tag(0x01);
+                self.tag(*b as u8);
+            }
+            DamlValue::Int64(i) => {
+                self.tag(0x02);
+                self.out.extend_from_slice(&i.to_be_bytes());
+            }
+            DamlValue::Numeric(d) => {
+                self.tag(0x03);
+                self.str(&d.normalize().to_string());
+            }
+            DamlValue::Text(s) => {
+                self.tag(0x04);
+                self.str(s);
+            }
+            DamlValue::Timestamp(t) => {
+                self.tag(0x05);
+                self.out.extend_from_slice(&t.timestamp_micros().to_be_bytes());
+            }
+            DamlValue::Date(d) => {
+                self.tag(0x06);
+                let days = d.signed_duration_since(chrono::NaiveDate::default()).num_days();
+                self.out.extend_from_slice(&days.to_be_bytes());
+            }
+            DamlValue::Party(p) => {
+                self.tag(0x07);
+                self.str(p.as_str());
+            }
+            DamlValue::ContractId(c) => {
+                self.tag(0x08);
+                self.str(c.as_str());
+            }
+            DamlValue::List(items) => {
+                self.tag(0x09);
+                self.len(items.len());
+                for item in items {
+                    self.value(item);
+                }
+            }
+            DamlValue::Optional(v) => {
+                self.tag(0x0a);
+                match v {
+                    Some(v) => {
+                        self.tag(1);
+                        self.value(v);
+                    }
+                    None => self.tag(0),
+                }
+            }
+            DamlValue::TextMap(map) => {
+                self.tag(0x0b);
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                self.len(entries.len());
+                for (k, v) in entries {
+                    self.str(k);
+                    self.value(v);
+                }
+            }
+            DamlValue::GenMap(entries) => {
+                self.tag(0x0c);
+                self.len(entries.len());
+                for (k, v) in entries {
+                    self.value(k);
+                    self.value(v);
+                }
+            }
+            DamlValue::Record(r) => {
+                self.tag(0x0d);
+                self.record(r);
+            }
+            DamlValue::Variant(v) => {
+                self.tag(0x0e);
+                self.opt_identifier(v.variant_id.as_ref());
+                self.str(&v.constructor);
+                self.value(&v.value);
+            }
+            DamlValue::Enum(e) => {
+                self.tag(0x0f);
+                self.opt_identifier(e.enum_id.as_ref());
+                self.str(&e.constructor);
+            }
+            DamlValue::Raw(raw) => {
+                self.tag(0x10);
+                self.bytes(raw);
+            }
+        }
+    }
+
+    fn command(&mut self, command: &Command) {
+        match command {
+            Command::Create(c) => {
+                self.tag(0x01);
+                self.identifier(&c.template_id);
+                self.record(&c.create_arguments);
+            }
+            Command::Exercise(c) => {
+                self.tag(0x02);
+                self.identifier(&c.template_id);
+                self.str(&c.contract_id);
+                self.str(&c.choice);
+                self.value(&c.choice_argument);
+            }
+            Command::ExerciseByKey(c) => {
+                self.tag(0x03);
+                self.identifier(&c.template_id);
+                self.value(&c.contract_key);
+                self.str(&c.choice);
+                self.value(&c.choice_argument);
+            }
+            Command::CreateAndExercise(c) => {
+                self.tag(0x04);
+                self.identifier(&c.template_id);
+                self.record(&c.create_arguments);
+                self.str(&c.choice);
+                self.value(&c.choice_argument);
+            }
+        }
+    }
+}
+
+impl DamlValue {
+    /// Deterministic encoding of this value (layout described in `types::canonical`).
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut enc = Encoder::default();
+        enc.value(self);
+        enc.out
+    }
+}
+
+impl Command {
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut enc = Encoder::default();
+        enc.command(self);
+        enc.out
+    }
+}
+
+impl Commands {
+    /// Deterministic, versioned encoding of the whole submission, suitable as signing input.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut enc = Encoder::default();
+        enc.bytes(CANONICAL_COMMANDS_V1);
+        enc.opt_str(self.ledger_id.as_deref());
+        enc.str(&self.workflow_id);
+        enc.str(&self.application_id);
+        enc.str(&self.command_id);
+        enc.sorted_strs(&self.act_as);
+        enc.sorted_strs(&self.read_as);
+        enc.len(self.commands.len());
+        for command in &self.commands {
+            enc.command(command);
+        }
+        enc.timestamp(self.min_ledger_time_abs.as_ref());
+        enc.duration(self.min_ledger_time_rel.as_ref());
+        enc.duration(self.deduplication_period.as_ref());
+        enc.opt_str(self.submission_id.as_deref());
+        enc.len(self.disclosed_contracts.len());
+        for disclosed in &self.disclosed_contracts {
+            enc.str(&disclosed.contract_id);
+            enc.identifier(&disclosed.template_id);
+            enc.bytes(&disclosed.created_event_blob);
+        }
+        enc.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::types::command::CreateCommand;
+
+    fn commands(act_as: &[&str], metadata: Vec<(&str, &str)>) -> Commands {
+        let mut map = HashMap::new();
+        for (k, v) in metadata {
+            map.insert(k.to_string(), DamlValue::text(v));
+        }
+        let template_id = Identifier::new("pkg", "Iou", "Iou");
+        Commands {
+            ledger_id: None,
+            workflow_id: "wf".into(),
+            application_id: "app".into(),
+            command_id: "cmd-1".into(),
+            act_as: act_as.iter().map(|s| s.to_string()).collect(),
+            read_as: Vec::new(),
+            commands: vec![Command::Create(CreateCommand {
+                template_id: template_id.clone(),
+                create_arguments: DamlRecord::new()
+                    .with_id(template_id)
+                    .field("owner", "alice")
+                    .field("meta", DamlValue::TextMap(map)),
+            })],
+            min_ledger_time_abs: None,
+            min_ledger_time_rel: None,
+            deduplication_period: Some(Duration::from_secs(30)),
+            submission_id: None,
+            disclosed_contracts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn canonical_bytes_ignore_insertion_order() {
+        let a = commands(&["alice", "bob"], vec![("a", "1"), ("b", "2"), ("c", "3")]);
+        let b = commands(&["bob", "alice"], vec![("c", "3"), ("a", "1"), ("b", "2")]);
+        assert_eq!(a.canonical_bytes(), b.canonical_bytes());
+        assert!(a.canonical_bytes().starts_with(&(CANONICAL_COMMANDS_V1.len() as u32).to_be_bytes()));
+
+        let c = commands(&["alice", "bob"], vec![("a", "1"), ("b", "2"), ("c", "4")]);
+        assert_ne!(a.canonical_bytes(), c.canonical_bytes());
+    }
+}
@@ -1,11 +1,13 @@
 //! Command types for Ledger API.
 //! See research/08, 04.
 
-use crate::types::identifier::Identifier;
+use crate::error::{SdkError, SdkResult};
+use crate::types::identifier::{Identifier, WorkflowId};
 use crate::types::value::{DamlRecord, DamlValue};
+use serde::{Deserialize, Serialize};
 
 /// Commands envelope for Ledger API submission.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Commands {
     pub ledger_id: Option<String>,
     pub workflow_id: String,
@@ -18,9 +20,79 @@ pub struct Commands {
     pub min_ledger_time_rel: Option<std::time::Duration>,
     pub deduplication_period: Option<std::time::Duration>,
     pub submission_id: Option<String>,
+    /// Contracts the submitters cannot see but were given out of band (explicit disclosure).
+    pub disclosed_contracts: Vec<DisclosedContract>,
 }
 
-#[derive(Debug, Clone)]
+impl Commands {
+    /// Set a validated workflow id in place of the free-form string.
+    pub fn with_workflow_id(&mut self, workflow_id: WorkflowId) -> &mut Self {
+        self.workflow_id = workflow_id.as_str().to_string();
+        self
+    }
+
+    /// Attach a disclosed contract that a command in this submission references.
+    pub fn with_disclosed_contract(&mut self, disclosed: DisclosedContract) -> &mut Self {
+        self.disclosed_contracts.push(disclosed);
+        self
+    }
+
+    /// Approximate serialized size: the length of the canonical encoding, which carries the
+    /// same strings and values as the protobuf message with comparable framing overhead.
+    pub fn estimated_size_bytes(&self) -> usize {
+        self.canonical_bytes().len()
+    }
+
+    /// Reject a submission the participant would refuse for its size, before sending it.
+    pub fn preflight(&self, limits: &ParticipantLimits) -> SdkResult<()> {
+        if self.commands.len() > limits.max_commands {
+            return Err(SdkError::Validation {
+                field: "commands".into(),
+                message: format!("{} commands exceed the limit of {}", self.commands.len(), limits.max_commands),
+            });
+        }
+        let size = self.estimated_size_bytes();
+        if size > limits.max_message_bytes {
+            return Err(SdkError::Validation {
+                field: "commands".into(),
+                message: format!(
+                    "estimated size {} bytes exceeds the limit of {} bytes",
+                    size, limits.max_message_bytes
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Participant-side submission limits checked by [`Commands::preflight`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParticipantLimits {
+    pub max_commands: usize,
+    /// Maximum inbound gRPC message size of the participant's Ledger API.
+    pub max_message_bytes: usize,
+}
+
+impl Default for ParticipantLimits {
+    /// No command cap; Canton's default 10 MiB inbound message limit.
+    fn default() -> Self {
+        Self {
+            max_commands: usize::MAX,
+            max_message_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// A contract shared with the submitter through explicit disclosure: its id, template and the
+/// `created_event_blob` obtained from a party that can see it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisclosedContract {
+    pub contract_id: String,
+    pub template_id: Identifier,
+    pub created_event_blob: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Command {
     Create(CreateCommand),
     Exercise(ExerciseCommand),
@@ -28,13 +100,13 @@ pub enum Command {
     CreateAndExercise(CreateAndExerciseCommand),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateCommand {
     pub template_id: Identifier,
     pub create_arguments: DamlRecord,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExerciseCommand {
     pub template_id: Identifier,
     pub contract_id: String,
@@ -42,7 +114,7 @@ pub struct ExerciseCommand {
     pub choice_argument: DamlValue,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExerciseByKeyCommand {
     pub template_id: Identifier,
     pub contract_key: DamlValue,
@@ -50,10 +122,66 @@ pub struct ExerciseByKeyCommand {
     pub choice_argument: DamlValue,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateAndExerciseCommand {
     pub template_id: Identifier,
     pub create_arguments: DamlRecord,
     pub choice: String,
     pub choice_argument: DamlValue,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands(count: usize, note: &str) -> Commands {
+        let template_id = Identifier::new("pkg", "Iou", "Iou");
+        Commands {
+            ledger_id: None,
+            workflow_id: String::new(),
+            application_id: "app".into(),
+            command_id: "cmd-1".into(),
+            act_as: vec!["alice".into()],
+            read_as: Vec::new(),
+            commands: (0..count)
+                .map(|_| {
+                    Command::Create(CreateCommand {
+                        template_id: template_id.clone(),
+                        create_arguments: DamlRecord::new().field("note", DamlValue::text(note)),
+                    })
+                })
+                .collect(),
+            min_ledger_time_abs: None,
+            min_ledger_time_rel: None,
+            deduplication_period: None,
+            submission_id: None,
+            disclosed_contracts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn preflight_checks_count_and_size() {
+        let limits = ParticipantLimits {
+            max_commands: 3,
+            max_message_bytes: 4096,
+        };
+        let small = commands(2, "hello");
+        assert!(small.estimated_size_bytes() > 0);
+        assert!(small.preflight(&limits).is_ok());
+
+        let too_many = commands(4, "hello");
+        assert!(matches!(too_many.preflight(&limits), Err(SdkError::Validation { message, .. }) if message.contains("4 commands")));
+
+        let too_large = commands(1, &"x".repeat(5000));
+        assert!(too_large.estimated_size_bytes() > 5000);
+        assert!(matches!(too_large.preflight(&limits), Err(SdkError::Validation { message, .. }) if message.contains("bytes")));
+        assert!(too_large.preflight(&ParticipantLimits::default()).is_ok());
+    }
+
+    #[test]
+    fn workflow_id_setter_uses_validated_id() {
+        let mut cmds = commands(1, "hello");
+        cmds.with_workflow_id(WorkflowId::new("settlement/Q1").unwrap());
+        assert_eq!(cmds.workflow_id, "settlement/Q1");
+    }
+}
@@ -0,0 +1,161 @@
+//! Command completions: the outcome of an asynchronously submitted command.
+//! See research/04 (CommandCompletionService).
+
+use crate::error::CantonErrorCode;
+use crate::types::identifier::PartyId;
+
+/// Outcome of one submitted command, as reported on the completion stream.
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub command_id: String,
+    pub status: CompletionStatus,
+    /// Update produced by the command; only set on success.
+    pub update_id: Option<String>,
+    pub submission_id: Option<String>,
+    pub act_as: Vec<PartyId>,
+    pub offset: String,
+}
+
+impl Completion {
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+}
+
+/// Completion status. Failures are classified by the Canton error-code id at the start of the
+/// status message; each carries the gRPC status code and full message from the ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionStatus {
+    Succeeded,
+    ContractNotFound { code: i32, message: String },
+    ContractKeyNotFound { code: i32, message: String },
+    DuplicateContractKey { code: i32, message: String },
+    /// Input contract was archived or is locked by a concurrent transaction.
+    ContractNotActive { code: i32, message: String },
+    DuplicateCommand { code: i32, message: String },
+    AuthorizationFailed { code: i32, message: String },
+    InvalidCommand { code: i32, message: String },
+    Timeout { code: i32, message: String },
+    SubmissionErrorUnknown { code: i32, message: String },
+}
+
+impl CompletionStatus {
+    /// Classify a gRPC status (`google.rpc.Status` code and message) from a completion or a
+    /// failed submission. Code 0 (OK) is success; unrecognized error ids map to
+    /// [`CompletionStatus::SubmissionErrorUnknown`].
+    pub fn from_grpc(code: i32, message: &str) -> CompletionStatus {
+        if code == 0 {
+            return CompletionStatus::Succeeded;
+        }
+        let id = CantonErrorCode::parse(message).map(|e| e.code).unwrap_or_default();
+        let message = message.to_string();
+        match id.as_str() {
+            "CONTRACT_NOT_FOUND" => CompletionStatus::ContractNotFound { code, message },
+            "CONTRACT_KEY_NOT_FOUND" => CompletionStatus::ContractKeyNotFound { code, message },
+            "DUPLICATE_CONTRACT_KEY" | "INCONSISTENT_CONTRACT_KEY" => {
+                CompletionStatus::DuplicateContractKey { code, message }
+            }
+            "CONTRACT_NOT_ACTIVE"
+            | "LOCAL_VERDICT_INACTIVE_CONTRACTS"
+            | "LOCAL_VERDICT_LOCKED_CONTRACTS"
+            | "INCONSISTENT_CONTRACTS" => CompletionStatus::ContractNotActive { code, message },
+            "DUPLICATE_COMMAND" | "SUBMISSION_ALREADY_IN_FLIGHT" => {
+                CompletionStatus::DuplicateCommand { code, message }
+            }
+            "DAML_AUTHORIZATION_ERROR" | "PERMISSION_DENIED" | "UNAUTHORIZED_PARTIES" => {
+                CompletionStatus::AuthorizationFailed { code, message }
+            }
+            "INVALID_ARGUMENT" | "MISSING_FIELD" | "INVALID_FIELD" | "DAML_INTERPRETATION_ERROR" => {
+                CompletionStatus::InvalidCommand { code, message }
+            }
+            "LOCAL_VERDICT_TIMEOUT" | "MEDIATOR_SAYS_TX_TIMED_OUT" | "REQUEST_TIME_OUT" => {
+                CompletionStatus::Timeout { code, message }
+            }
+            _ => CompletionStatus::SubmissionErrorUnknown { code, message },
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, CompletionStatus::Succeeded)
+    }
+
+    /// gRPC status code; 0 for success.
+    pub fn code(&self) -> i32 {
+        match self {
+            CompletionStatus::Succeeded => 0,
+            CompletionStatus::ContractNotFound { code, .. }
+            | CompletionStatus::ContractKeyNotFound { code, .. }
+            | CompletionStatus::DuplicateContractKey { code, .. }
+            | CompletionStatus::ContractNotActive { code, .. }
+            | CompletionStatus::DuplicateCommand { code, .. }
+            | CompletionStatus::AuthorizationFailed { code, .. }
+            | CompletionStatus::InvalidCommand { code, .. }
+            | CompletionStatus::Timeout { code, .. }
+            | CompletionStatus::SubmissionErrorUnknown { code, .. } => *code,
+        }
+    }
+
+    /// Ledger-provided status message; empty for success.
+    pub fn message(&self) -> &str {
+        match self {
+            CompletionStatus::Succeeded => "",
+            CompletionStatus::ContractNotFound { message, .. }
+            | CompletionStatus::ContractKeyNotFound { message, .. }
+            | CompletionStatus::DuplicateContractKey { message, .. }
+            | CompletionStatus::ContractNotActive { message, .. }
+            | CompletionStatus::DuplicateCommand { message, .. }
+            | CompletionStatus::AuthorizationFailed { message, .. }
+            | CompletionStatus::InvalidCommand { message, .. }
+            | CompletionStatus::Timeout { message, .. }
+            | CompletionStatus::SubmissionErrorUnknown { message, .. } => message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_grpc_classifies_canton_error_ids() {
+        let cases = [
+            (
+                5,
+                "CONTRACT_NOT_FOUND(11,4f2a9c1d): Contract could not be found with id 00abc",
+                "ContractNotFound",
+            ),
+            (
+                6,
+                "DUPLICATE_COMMAND(10,1b7e0c22): A command with the given command id has already been successfully processed",
+                "DuplicateCommand",
+            ),
+            (
+                9,
+                "LOCAL_VERDICT_LOCKED_CONTRACTS(2,0d0f9b3e): Rejected transaction is referring to locked contracts",
+                "ContractNotActive",
+            ),
+            (
+                3,
+                "DAML_AUTHORIZATION_ERROR(8,77aa01ff): Interpretation error: Error: node NodeId(0) requires authorizers Alice",
+                "AuthorizationFailed",
+            ),
+            (13, "SOMETHING_NEW(4,00): unexpected", "Unknown"),
+            (14, "connection reset", "Unknown"),
+        ];
+        for (code, message, expected) in cases {
+            let status = CompletionStatus::from_grpc(code, message);
+            let kind = match &status {
+                CompletionStatus::ContractNotFound { .. } => "ContractNotFound",
+                CompletionStatus::DuplicateCommand { .. } => "DuplicateCommand",
+                CompletionStatus::ContractNotActive { .. } => "ContractNotActive",
+                CompletionStatus::AuthorizationFailed { .. } => "AuthorizationFailed",
+                CompletionStatus::SubmissionErrorUnknown { .. } => "Unknown",
+                other => panic!("unexpected status {:?}", other),
+            };
+            assert_eq!(kind, expected, "{}", message);
+            assert_eq!(status.code(), code);
+            assert_eq!(status.message(), message);
+        }
+        assert!(CompletionStatus::from_grpc(0, "").is_success());
+    }
+}
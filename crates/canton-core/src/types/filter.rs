@@ -1,7 +1,7 @@
 //! Transaction filter for Ledger API.
 //! See research/08, 04.
 
-use crate::types::identifier::Identifier;
+use crate::types::identifier::{Identifier, PartyId};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
@@ -25,3 +25,22 @@ pub struct InterfaceFilter {
     pub interface_id: Identifier,
     pub include_created_event_blob: bool,
 }
+
+impl TransactionFilter {
+    /// Events visible to `party` for every template implementing `interface_id`, including
+    /// the interface view.
+    pub fn for_interface(party: &PartyId, interface_id: Identifier) -> Self {
+        let filters = Filters {
+            inclusive: Some(InclusiveFilters {
+                template_ids: Vec::new(),
+                interface_filters: vec![InterfaceFilter {
+                    interface_id,
+                    include_created_event_blob: false,
+                }],
+            }),
+        };
+        Self {
+            filters_by_party: HashMap::from([(party.to_string(), filters)]),
+        }
+    }
+}
@@ -1,12 +1,13 @@
-//! Identifier types: PartyId, ContractId, Identifier.
+//! Identifier types: PartyId, ContractId, WorkflowId, Identifier.
 //! See research/08 §3.3; Party ID format partyHint::fingerprint (research/06, 09).
 
 use std::fmt;
 use std::str::FromStr;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Template/type identifier (package:module.entity).
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Identifier {
     pub package_id: String,
     pub module_name: String,
@@ -59,7 +60,7 @@ impl fmt::Display for Identifier {
 }
 
 /// Party identifier. Canton external party format: partyHint::fingerprint (research/06, 09).
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PartyId(pub String);
 
 impl PartyId {
@@ -89,6 +90,22 @@ impl PartyId {
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Party hint of a structured `partyHint::fingerprint` id; `None` for unstructured ids.
+    pub fn hint(&self) -> Option<&str> {
+        self.split().map(|(hint, _)| hint)
+    }
+
+    /// Namespace fingerprint of a structured `partyHint::fingerprint` id.
+    pub fn fingerprint(&self) -> Option<&str> {
+        self.split().map(|(_, fingerprint)| fingerprint)
+    }
+
+    fn split(&self) -> Option<(&str, &str)> {
+        self.0
+            .split_once("::")
+            .filter(|(hint, fingerprint)| !hint.is_empty() && !fingerprint.is_empty())
+    }
 }
 
 impl fmt::Display for PartyId {
@@ -106,7 +123,7 @@ impl FromStr for PartyId {
 }
 
 /// Contract identifier.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ContractId(pub String);
 
 impl ContractId {
@@ -132,6 +149,50 @@ impl From<&str> for ContractId {
     fn from(s: &str) -> Self { Self(s.to_string()) }
 }
 
+/// Workflow identifier. Ledger API `LedgerString`: at most 255 characters from
+/// `[A-Za-z0-9#:-_/ ]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WorkflowId(String);
+
+impl WorkflowId {
+    pub const MAX_LENGTH: usize = 255;
+
+    pub fn new(id: impl Into<String>) -> Result<Self, ValidationError> {
+        let id = id.into();
+        if id.is_empty() {
+            return Err(ValidationError::Empty("workflow_id"));
+        }
+        if id.len() > Self::MAX_LENGTH {
+            return Err(ValidationError::TooLong("workflow_id", Self::MAX_LENGTH));
+        }
+        if !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '#' | ':' | '-' | '_' | '/' | ' '))
+        {
+            return Err(ValidationError::InvalidCharacters("workflow_id"));
+        }
+        Ok(Self(id))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for WorkflowId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for WorkflowId {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ParseError {
     #[error("Invalid format: {0}")]
@@ -147,3 +208,34 @@ pub enum ValidationError {
     #[error("{0} contains invalid characters")]
     InvalidCharacters(&'static str),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn party_id_splits_hint_and_fingerprint() {
+        let party = PartyId::new("alice::1220abcdef").unwrap();
+        assert_eq!(party.hint(), Some("alice"));
+        assert_eq!(party.fingerprint(), Some("1220abcdef"));
+
+        for unstructured in ["alice", "alice::", "::1220abcdef"] {
+            let party = PartyId::new(unstructured).unwrap();
+            assert_eq!(party.hint(), None, "{}", unstructured);
+            assert_eq!(party.fingerprint(), None, "{}", unstructured);
+        }
+    }
+
+    #[test]
+    fn workflow_id_enforces_ledger_string_rules() {
+        for valid in ["settlement-2024/Q1", "trade#42", "a:b_c d"] {
+            assert_eq!(WorkflowId::new(valid).unwrap().as_str(), valid);
+        }
+        assert!(matches!(WorkflowId::new(""), Err(ValidationError::Empty(_))));
+        assert!(matches!(WorkflowId::new("x".repeat(256)), Err(ValidationError::TooLong(_, 255))));
+        assert!(WorkflowId::new("x".repeat(255)).is_ok());
+        for invalid in ["wf.1", "wf\n", "ворк"] {
+            assert!(matches!(WorkflowId::new(invalid), Err(ValidationError::InvalidCharacters(_))), "{}", invalid);
+        }
+    }
+}
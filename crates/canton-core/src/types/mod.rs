@@ -6,6 +6,9 @@ pub mod command;
 pub mod transaction;
 pub mod filter;
 pub mod offset;
+pub mod completion;
+pub mod party;
+pub mod canonical;
 
 pub use identifier::*;
 pub use value::*;
@@ -14,3 +17,6 @@ pub use command::*;
 pub use transaction::*;
 pub use filter::*;
 pub use offset::*;
+pub use completion::*;
+pub use party::*;
+pub use canonical::CANONICAL_COMMANDS_V1;
@@ -0,0 +1,23 @@
+//! Party management types.
+//! See research/04 (PartyManagementService).
+
+use std::collections::HashMap;
+
+use crate::types::identifier::PartyId;
+
+/// Participant-local view of a party.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartyDetails {
+    pub party: PartyId,
+    /// Whether the party is hosted on the participant serving the request.
+    pub is_local: bool,
+    pub display_name: Option<String>,
+    /// Participant-local annotations (`ObjectMeta.annotations`).
+    pub annotations: HashMap<String, String>,
+    pub identity_provider_id: Option<String>,
+}
+
+impl PartyDetails {
+    /// Annotation key used to keep a display name (Ledger API v2 has no dedicated field).
+    pub const DISPLAY_NAME_ANNOTATION: &'static str = "display_name";
+}
@@ -1,13 +1,15 @@
 //! Daml value types.
 //! See research/08-sdk-architecture-design.md §3.2.
 
+use crate::error::{SdkError, SdkResult};
 use crate::types::identifier::{ContractId, Identifier, PartyId};
 use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Daml value representation.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DamlValue {
     Unit,
     Bool(bool),
@@ -25,28 +27,31 @@ pub enum DamlValue {
     Record(DamlRecord),
     Variant(DamlVariant),
     Enum(DamlEnum),
+    /// Ledger API value the SDK could not model (e.g. a value kind newer than this client),
+    /// kept as its encoded proto bytes. Only produced when decoding with a preserve-raw policy.
+    Raw(bytes::Bytes),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DamlRecord {
     pub record_id: Option<Identifier>,
     pub fields: Vec<RecordField>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RecordField {
     pub label: String,
     pub value: DamlValue,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DamlVariant {
     pub variant_id: Option<Identifier>,
     pub constructor: String,
     pub value: Box<DamlValue>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DamlEnum {
     pub enum_id: Option<Identifier>,
     pub constructor: String,
@@ -109,12 +114,109 @@ impl DamlRecord {
             .find(|f| f.label == name)
             .map(|f| &f.value)
     }
+
+    /// Field-level changes from `self` to `other` (e.g. a contract's create arguments before and
+    /// after an exercise). Nested records are compared field by field, with dotted paths.
+    pub fn diff(&self, other: &DamlRecord) -> Vec<FieldDiff> {
+        let mut diffs = Vec::new();
+        diff_records("", self, other, &mut diffs);
+        diffs
+    }
+}
+
+/// One difference reported by [`DamlRecord::diff`]; `path` is the dotted field label.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldDiff {
+    Added { path: String, value: DamlValue },
+    Removed { path: String, value: DamlValue },
+    Changed { path: String, old: DamlValue, new: DamlValue },
+}
+
+fn diff_records(prefix: &str, old: &DamlRecord, new: &DamlRecord, diffs: &mut Vec<FieldDiff>) {
+    let path = |label: &str| {
+        if prefix.is_empty() { label.to_string() } else { format!("{}.{}", prefix, label) }
+    };
+    for field in &old.fields {
+        match (&field.value, new.get(&field.label)) {
+            (_, None) => diffs.push(FieldDiff::Removed {
+                path: path(&field.label),
+                value: field.value.clone(),
+            }),
+            (DamlValue::Record(old_record), Some(DamlValue::Record(new_record))) => {
+                diff_records(&path(&field.label), old_record, new_record, diffs);
+            }
+            (_, Some(value)) if *value != field.value => diffs.push(FieldDiff::Changed {
+                path: path(&field.label),
+                old: field.value.clone(),
+                new: value.clone(),
+            }),
+            _ => {}
+        }
+    }
+    for field in &new.fields {
+        if old.get(&field.label).is_none() {
+            diffs.push(FieldDiff::Added {
+                path: path(&field.label),
+                value: field.value.clone(),
+            });
+        }
+    }
 }
 
 impl Default for DamlRecord {
     fn default() -> Self { Self::new() }
 }
 
+/// Typed builder for command arguments. Declared `required` labels must be set before
+/// [`RecordBuilder::build`] succeeds; setting a label twice keeps the last value.
+#[derive(Debug, Clone, Default)]
+pub struct RecordBuilder {
+    record_id: Option<Identifier>,
+    required: Vec<String>,
+    fields: Vec<RecordField>,
+}
+
+impl RecordBuilder {
+    pub fn new(record_id: Identifier) -> Self {
+        Self { record_id: Some(record_id), ..Self::default() }
+    }
+
+    pub fn required(mut self, labels: &[&str]) -> Self {
+        self.required.extend(labels.iter().map(|l| l.to_string()));
+        self
+    }
+
+    pub fn value(mut self, label: impl Into<String>, value: impl Into<DamlValue>) -> Self {
+        let label = label.into();
+        let value = value.into();
+        match self.fields.iter_mut().find(|f| f.label == label) {
+            Some(field) => field.value = value,
+            None => self.fields.push(RecordField { label, value }),
+        }
+        self
+    }
+
+    pub fn text(self, label: impl Into<String>, v: impl Into<String>) -> Self { self.value(label, DamlValue::Text(v.into())) }
+    pub fn int64(self, label: impl Into<String>, v: i64) -> Self { self.value(label, DamlValue::Int64(v)) }
+    pub fn numeric(self, label: impl Into<String>, v: Decimal) -> Self { self.value(label, DamlValue::Numeric(v)) }
+    pub fn bool(self, label: impl Into<String>, v: bool) -> Self { self.value(label, DamlValue::Bool(v)) }
+    pub fn party(self, label: impl Into<String>, v: PartyId) -> Self { self.value(label, DamlValue::Party(v)) }
+    pub fn contract_id(self, label: impl Into<String>, v: ContractId) -> Self { self.value(label, DamlValue::ContractId(v)) }
+    pub fn timestamp(self, label: impl Into<String>, v: DateTime<Utc>) -> Self { self.value(label, DamlValue::Timestamp(v)) }
+    pub fn date(self, label: impl Into<String>, v: NaiveDate) -> Self { self.value(label, DamlValue::Date(v)) }
+    pub fn record(self, label: impl Into<String>, v: DamlRecord) -> Self { self.value(label, DamlValue::Record(v)) }
+
+    pub fn build(self) -> SdkResult<DamlRecord> {
+        if let Some(missing) = self.required.iter().find(|r| !self.fields.iter().any(|f| &f.label == *r)) {
+            return Err(SdkError::Validation {
+                field: missing.clone(),
+                message: "required record field not set".into(),
+            });
+        }
+        Ok(DamlRecord { record_id: self.record_id, fields: self.fields })
+    }
+}
+
 impl From<bool> for DamlValue {
     fn from(v: bool) -> Self { Self::Bool(v) }
 }
@@ -140,3 +242,59 @@ impl<T: Into<DamlValue>> From<Option<T>> for DamlValue {
 impl From<DamlRecord> for DamlValue {
     fn from(v: DamlRecord) -> Self { Self::Record(v) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_builder_checks_required_fields() {
+        let iou = Identifier::new("pkg", "Iou", "Iou");
+        let alice = PartyId::new("alice::1220ab").unwrap();
+
+        let record = RecordBuilder::new(iou.clone())
+            .required(&["issuer", "owner", "amount"])
+            .party("issuer", alice.clone())
+            .party("owner", alice.clone())
+            .numeric("amount", Decimal::new(1050, 2))
+            .text("currency", "USD")
+            .build()
+            .unwrap();
+        assert_eq!(record.record_id, Some(iou.clone()));
+        assert_eq!(record.get("amount"), Some(&DamlValue::Numeric(Decimal::new(1050, 2))));
+        assert_eq!(record.fields.len(), 4);
+
+        let err = RecordBuilder::new(iou)
+            .required(&["issuer", "owner"])
+            .party("issuer", alice)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, SdkError::Validation { ref field, .. } if field == "owner"));
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_nested_changes() {
+        let old = DamlRecord::new()
+            .field("owner", DamlValue::text("alice"))
+            .field("note", DamlValue::text("draft"))
+            .field("terms", DamlRecord::new().field("amount", DamlValue::int64(10)).field("days", DamlValue::int64(30)));
+        let new = DamlRecord::new()
+            .field("owner", DamlValue::text("alice"))
+            .field("terms", DamlRecord::new().field("amount", DamlValue::int64(12)).field("days", DamlValue::int64(30)))
+            .field("observer", DamlValue::text("bob"));
+
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                FieldDiff::Removed { path: "note".into(), value: DamlValue::text("draft") },
+                FieldDiff::Changed {
+                    path: "terms.amount".into(),
+                    old: DamlValue::int64(10),
+                    new: DamlValue::int64(12),
+                },
+                FieldDiff::Added { path: "observer".into(), value: DamlValue::text("bob") },
+            ]
+        );
+        assert!(new.diff(&new).is_empty());
+    }
+}
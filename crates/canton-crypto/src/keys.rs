@@ -16,3 +16,11 @@ pub enum KeyPurpose {
     NamespaceDelegation,
     IdentityBinding,
 }
+
+impl KeyPurpose {
+    /// Every purpose except `Encryption` is a signing role (namespace delegations and identity
+    /// bindings are signatures over topology transactions).
+    pub fn can_sign(self) -> bool {
+        !matches!(self, KeyPurpose::Encryption)
+    }
+}
@@ -1,31 +1,80 @@
 //! In-memory key store implementation.
 //! See research/06-cryptographic-requirements.md §3.2.
 
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 
+use aes_gcm::aead::{Aead, AeadCore, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
 use async_trait::async_trait;
 use ed25519_dalek::SigningKey;
+use hkdf::Hkdf;
 use rand::rngs::OsRng;
+use sha2::Sha256;
+use zeroize::Zeroizing;
 
 use crate::keys::{KeyAlgorithm, KeyPurpose};
 use crate::keystore::{
-    KeyFingerprint, KeyInfo, KeyMetadata, KeyStore, KeyStoreError, Signature,
+    verify_signature, KeyFingerprint, KeyInfo, KeyMetadata, KeyStore, KeyStoreError, Signature,
 };
 
 struct StoredKey {
     signing_key: Option<SigningKeyInner>,
     verifying_key: VerifyingKeyInner,
     fingerprint: KeyFingerprint,
+    purpose: KeyPurpose,
     metadata: KeyMetadata,
 }
 
+impl StoredKey {
+    fn require_signing(&self, operation: &'static str) -> Result<(), KeyStoreError> {
+        if self.purpose.can_sign() {
+            Ok(())
+        } else {
+            Err(self.wrong_purpose(operation))
+        }
+    }
+
+    fn wrong_purpose(&self, operation: &'static str) -> KeyStoreError {
+        KeyStoreError::WrongKeyPurpose {
+            fingerprint: self.fingerprint.to_hex(),
+            actual: self.purpose,
+            operation,
+        }
+    }
+
+    /// AES-256-GCM cipher for an `Encryption` key: HKDF-SHA256 over the secret key bytes,
+    /// salted with the fingerprint.
+    fn cipher(&self, operation: &'static str) -> Result<Aes256Gcm, KeyStoreError> {
+        if self.purpose != KeyPurpose::Encryption {
+            return Err(self.wrong_purpose(operation));
+        }
+        // Borrowed, not copied: no unwiped temporary of the secret is made.
+        let secret: &[u8] = match self.signing_key.as_ref() {
+            Some(SigningKeyInner::Ed25519(k)) => k.as_bytes(),
+            Some(SigningKeyInner::EcdsaP256(k)) | Some(SigningKeyInner::EcdsaSecp256k1(k)) => k,
+            None => return Err(KeyStoreError::AccessDenied("No secret key".into())),
+        };
+        let mut key = Zeroizing::new([0u8; 32]);
+        Hkdf::<Sha256>::new(Some(&self.fingerprint.0), secret)
+            .expand(AES_KEY_INFO, key.as_mut())
+            .map_err(|e| KeyStoreError::CryptoError(e.to_string()))?;
+        Ok(Aes256Gcm::new(&(*key).into()))
+    }
+}
+
+const AES_KEY_INFO: &[u8] = b"canton-sdk/keystore/aes-256-gcm/v1";
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// Secret halves. All variants wipe their bytes on drop: dalek's `SigningKey` is
+/// `ZeroizeOnDrop`, raw ECDSA scalars are held in `Zeroizing`.
 #[derive(Clone)]
 enum SigningKeyInner {
     Ed25519(ed25519_dalek::SigningKey),
-    EcdsaP256(Vec<u8>),
-    EcdsaSecp256k1(Vec<u8>),
+    EcdsaP256(Zeroizing<Vec<u8>>),
+    EcdsaSecp256k1(Zeroizing<Vec<u8>>),
 }
 
 #[derive(Clone)]
@@ -38,13 +87,56 @@ enum VerifyingKeyInner {
 /// In-memory key store (development/testing).
 pub struct InMemoryKeyStore {
     keys: Arc<RwLock<HashMap<KeyFingerprint, StoredKey>>>,
+    /// Nonces used per key, when nonce tracking is on.
+    used_nonces: Option<Arc<Mutex<UsedNonces>>>,
 }
 
+type UsedNonces = HashSet<(KeyFingerprint, [u8; NONCE_LEN])>;
+
 impl InMemoryKeyStore {
     pub fn new() -> Self {
         Self {
             keys: Arc::new(RwLock::new(HashMap::new())),
+            used_nonces: None,
+        }
+    }
+
+    /// Store that remembers every AES-GCM nonce it has used and refuses to encrypt twice
+    /// under the same key and nonce. Memory grows with the number of encryptions.
+    pub fn with_nonce_tracking() -> Self {
+        Self {
+            used_nonces: Some(Arc::default()),
+            ..Self::new()
+        }
+    }
+
+    async fn encrypt_with_nonce(
+        &self,
+        fingerprint: &KeyFingerprint,
+        nonce: [u8; NONCE_LEN],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, KeyStoreError> {
+        let keys = self.keys.read().await;
+        let stored = keys
+            .get(fingerprint)
+            .ok_or_else(|| KeyStoreError::KeyNotFound(fingerprint.to_hex()))?;
+        if stored.metadata.is_expired(chrono::Utc::now()) {
+            return Err(KeyStoreError::KeyExpired(fingerprint.to_hex()));
+        }
+        let cipher = stored.cipher("encryption")?;
+        if let Some(used) = &self.used_nonces {
+            let mut used = used.lock().unwrap_or_else(|e| e.into_inner());
+            if !used.insert((fingerprint.clone(), nonce)) {
+                return Err(KeyStoreError::NonceReuse(fingerprint.to_hex()));
+            }
         }
+        let ciphertext = cipher
+            .encrypt(&Nonce::from(nonce), plaintext)
+            .map_err(|e| KeyStoreError::CryptoError(e.to_string()))?;
+        let mut out = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
     }
 }
 
@@ -59,7 +151,7 @@ impl KeyStore for InMemoryKeyStore {
     async fn generate_key(
         &self,
         algorithm: KeyAlgorithm,
-        _purpose: KeyPurpose,
+        purpose: KeyPurpose,
         metadata: KeyMetadata,
     ) -> Result<KeyFingerprint, KeyStoreError> {
         let (signing_inner, verifying_inner, fingerprint) = match algorithm {
@@ -80,7 +172,7 @@ impl KeyStore for InMemoryKeyStore {
                 let pk = verifying.to_encoded_point(false);
                 let fp = KeyFingerprint::compute(pk.as_bytes(), algorithm);
                 (
-                    SigningKeyInner::EcdsaP256(signing.to_bytes().to_vec()),
+                    SigningKeyInner::EcdsaP256(Zeroizing::new(Zeroizing::new(signing.to_bytes()).to_vec())),
                     VerifyingKeyInner::EcdsaP256(pk.as_bytes().to_vec()),
                     fp,
                 )
@@ -92,7 +184,7 @@ impl KeyStore for InMemoryKeyStore {
                 let pk = verifying.to_encoded_point(false);
                 let fp = KeyFingerprint::compute(pk.as_bytes(), algorithm);
                 (
-                    SigningKeyInner::EcdsaSecp256k1(signing.to_bytes().to_vec()),
+                    SigningKeyInner::EcdsaSecp256k1(Zeroizing::new(Zeroizing::new(signing.to_bytes()).to_vec())),
                     VerifyingKeyInner::EcdsaSecp256k1(pk.as_bytes().to_vec()),
                     fp,
                 )
@@ -108,6 +200,7 @@ impl KeyStore for InMemoryKeyStore {
             signing_key: Some(signing_inner),
             verifying_key: verifying_inner,
             fingerprint: fingerprint.clone(),
+            purpose,
             metadata,
         };
         self.keys.write().await.insert(fingerprint.clone(), stored);
@@ -118,7 +211,7 @@ impl KeyStore for InMemoryKeyStore {
         &self,
         key_bytes: &[u8],
         algorithm: KeyAlgorithm,
-        _purpose: KeyPurpose,
+        purpose: KeyPurpose,
         metadata: KeyMetadata,
     ) -> Result<KeyFingerprint, KeyStoreError> {
         let (signing_inner, verifying_inner, fingerprint) = match algorithm {
@@ -144,7 +237,7 @@ impl KeyStore for InMemoryKeyStore {
                 let pk = verifying.to_encoded_point(false);
                 let fp = KeyFingerprint::compute(pk.as_bytes(), algorithm);
                 (
-                    SigningKeyInner::EcdsaP256(key_bytes.to_vec()),
+                    SigningKeyInner::EcdsaP256(Zeroizing::new(key_bytes.to_vec())),
                     VerifyingKeyInner::EcdsaP256(pk.as_bytes().to_vec()),
                     fp,
                 )
@@ -157,7 +250,7 @@ impl KeyStore for InMemoryKeyStore {
                 let pk = verifying.to_encoded_point(false);
                 let fp = KeyFingerprint::compute(pk.as_bytes(), algorithm);
                 (
-                    SigningKeyInner::EcdsaSecp256k1(key_bytes.to_vec()),
+                    SigningKeyInner::EcdsaSecp256k1(Zeroizing::new(key_bytes.to_vec())),
                     VerifyingKeyInner::EcdsaSecp256k1(pk.as_bytes().to_vec()),
                     fp,
                 )
@@ -173,6 +266,7 @@ impl KeyStore for InMemoryKeyStore {
             signing_key: Some(signing_inner),
             verifying_key: verifying_inner,
             fingerprint: fingerprint.clone(),
+            purpose,
             metadata,
         };
         self.keys.write().await.insert(fingerprint.clone(), stored);
@@ -201,6 +295,10 @@ impl KeyStore for InMemoryKeyStore {
         let stored = keys
             .get(fingerprint)
             .ok_or_else(|| KeyStoreError::KeyNotFound(fingerprint.to_hex()))?;
+        stored.require_signing("signing")?;
+        if stored.metadata.is_expired(chrono::Utc::now()) {
+            return Err(KeyStoreError::KeyExpired(fingerprint.to_hex()));
+        }
         let signing_key = stored
             .signing_key
             .as_ref()
@@ -238,34 +336,46 @@ impl KeyStore for InMemoryKeyStore {
         let stored = keys
             .get(fingerprint)
             .ok_or_else(|| KeyStoreError::KeyNotFound(fingerprint.to_hex()))?;
-        let ok = match &stored.verifying_key {
-            VerifyingKeyInner::Ed25519(k) => {
-                use ed25519_dalek::{Signature as EdSig, Verifier};
-                let sig = EdSig::from_bytes(
-                    signature.bytes.as_slice().try_into().map_err(|_| {
-                        KeyStoreError::InvalidKeyFormat("Invalid Ed25519 signature length".into())
-                    })?,
-                );
-                k.verify(data, &sig).is_ok()
-            }
-            VerifyingKeyInner::EcdsaP256(key_bytes) => {
-                use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
-                let k = VerifyingKey::from_sec1_bytes(key_bytes)
-                    .map_err(|e| KeyStoreError::CryptoError(e.to_string()))?;
-                let sig = Signature::from_slice(&signature.bytes)
-                    .map_err(|e| KeyStoreError::CryptoError(e.to_string()))?;
-                k.verify(data, &sig).is_ok()
-            }
-            VerifyingKeyInner::EcdsaSecp256k1(key_bytes) => {
-                use k256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
-                let k = VerifyingKey::from_sec1_bytes(key_bytes)
-                    .map_err(|e| KeyStoreError::CryptoError(e.to_string()))?;
-                let sig = Signature::from_slice(&signature.bytes)
-                    .map_err(|e| KeyStoreError::CryptoError(e.to_string()))?;
-                k.verify(data, &sig).is_ok()
-            }
+        stored.require_signing("verification")?;
+        let (algorithm, public_key) = match &stored.verifying_key {
+            VerifyingKeyInner::Ed25519(k) => (KeyAlgorithm::Ed25519, k.as_bytes().as_slice()),
+            VerifyingKeyInner::EcdsaP256(k) => (KeyAlgorithm::EcdsaP256, k.as_slice()),
+            VerifyingKeyInner::EcdsaSecp256k1(k) => (KeyAlgorithm::EcdsaSecp256k1, k.as_slice()),
         };
-        Ok(ok)
+        verify_signature(algorithm, public_key, data, signature)
+    }
+
+    async fn encrypt(
+        &self,
+        fingerprint: &KeyFingerprint,
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, KeyStoreError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        self.encrypt_with_nonce(fingerprint, nonce.into(), plaintext).await
+    }
+
+    async fn decrypt(
+        &self,
+        fingerprint: &KeyFingerprint,
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, KeyStoreError> {
+        let keys = self.keys.read().await;
+        let stored = keys
+            .get(fingerprint)
+            .ok_or_else(|| KeyStoreError::KeyNotFound(fingerprint.to_hex()))?;
+        let cipher = stored.cipher("decryption")?;
+        if ciphertext.len() < NONCE_LEN + TAG_LEN {
+            return Err(KeyStoreError::CryptoError(format!(
+                "ciphertext too short: {} bytes, need at least {}",
+                ciphertext.len(),
+                NONCE_LEN + TAG_LEN
+            )));
+        }
+        let (nonce, body) = ciphertext.split_at(NONCE_LEN);
+        let nonce: [u8; NONCE_LEN] = nonce.try_into().expect("split at NONCE_LEN");
+        cipher
+            .decrypt(&Nonce::from(nonce), body)
+            .map_err(|_| KeyStoreError::CryptoError("decryption failed".into()))
     }
 
     async fn delete_key(&self, fingerprint: &KeyFingerprint) -> Result<(), KeyStoreError> {
@@ -289,7 +399,7 @@ impl KeyStore for InMemoryKeyStore {
                     VerifyingKeyInner::EcdsaP256(_) => KeyAlgorithm::EcdsaP256,
                     VerifyingKeyInner::EcdsaSecp256k1(_) => KeyAlgorithm::EcdsaSecp256k1,
                 },
-                purpose: KeyPurpose::Signing,
+                purpose: stored.purpose,
                 public_key: pk,
                 metadata: stored.metadata.clone(),
             });
@@ -297,6 +407,28 @@ impl KeyStore for InMemoryKeyStore {
         Ok(out)
     }
 
+    async fn exists(&self, fingerprint: &KeyFingerprint) -> Result<bool, KeyStoreError> {
+        Ok(self.keys.read().await.contains_key(fingerprint))
+    }
+
+    async fn count(&self) -> Result<usize, KeyStoreError> {
+        Ok(self.keys.read().await.len())
+    }
+
+    async fn set_tag(
+        &self,
+        fingerprint: &KeyFingerprint,
+        key: &str,
+        value: &str,
+    ) -> Result<(), KeyStoreError> {
+        let mut keys = self.keys.write().await;
+        let stored = keys
+            .get_mut(fingerprint)
+            .ok_or_else(|| KeyStoreError::KeyNotFound(fingerprint.to_hex()))?;
+        stored.metadata.tags.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
     async fn get_key_info(&self, fingerprint: &KeyFingerprint) -> Result<KeyInfo, KeyStoreError> {
         let keys = self.keys.read().await;
         let stored = keys
@@ -314,9 +446,182 @@ impl KeyStore for InMemoryKeyStore {
                 VerifyingKeyInner::EcdsaP256(_) => KeyAlgorithm::EcdsaP256,
                 VerifyingKeyInner::EcdsaSecp256k1(_) => KeyAlgorithm::EcdsaSecp256k1,
             },
-            purpose: KeyPurpose::Signing,
+            purpose: stored.purpose,
             public_key: pk,
             metadata: stored.metadata.clone(),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(expires_at: Option<chrono::DateTime<chrono::Utc>>) -> KeyMetadata {
+        KeyMetadata {
+            name: None,
+            description: None,
+            tags: HashMap::new(),
+            created_at: chrono::Utc::now(),
+            expires_at,
+        }
+    }
+
+    /// Compile-time check that every secret variant wipes on drop: changing a field back to a
+    /// plain `Vec<u8>` (or to a non-zeroizing key type) stops this from compiling.
+    #[test]
+    fn secret_key_fields_are_zeroizing() {
+        fn zeroize_on_drop<T: zeroize::ZeroizeOnDrop>(_: &T) {}
+        fn zeroizing(_: &Zeroizing<Vec<u8>>) {}
+        fn check(secret: &SigningKeyInner) {
+            match secret {
+                SigningKeyInner::Ed25519(k) => zeroize_on_drop(k),
+                SigningKeyInner::EcdsaP256(k) | SigningKeyInner::EcdsaSecp256k1(k) => zeroizing(k),
+            }
+        }
+        check(&SigningKeyInner::EcdsaP256(Zeroizing::new(vec![1; 32])));
+        check(&SigningKeyInner::Ed25519(SigningKey::from_bytes(&[7; 32])));
+    }
+
+    #[tokio::test]
+    async fn expired_key_refuses_to_sign() {
+        let store = InMemoryKeyStore::new();
+        let past = chrono::Utc::now() - chrono::Duration::hours(1);
+        let future = chrono::Utc::now() + chrono::Duration::hours(1);
+
+        let expired = store
+            .generate_key(KeyAlgorithm::Ed25519, KeyPurpose::Signing, metadata(Some(past)))
+            .await
+            .unwrap();
+        assert!(matches!(
+            store.sign(&expired, b"payload").await,
+            Err(KeyStoreError::KeyExpired(_))
+        ));
+        assert!(store.get_key_info(&expired).await.unwrap().is_expired(chrono::Utc::now()));
+
+        let valid = store
+            .generate_key(KeyAlgorithm::Ed25519, KeyPurpose::Signing, metadata(Some(future)))
+            .await
+            .unwrap();
+        assert!(store.sign(&valid, b"payload").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn encryption_key_cannot_sign() {
+        let store = InMemoryKeyStore::new();
+        let key = store
+            .generate_key(KeyAlgorithm::Ed25519, KeyPurpose::Encryption, metadata(None))
+            .await
+            .unwrap();
+        assert!(matches!(
+            store.sign(&key, b"payload").await,
+            Err(KeyStoreError::WrongKeyPurpose { actual: KeyPurpose::Encryption, .. })
+        ));
+        assert_eq!(store.get_key_info(&key).await.unwrap().purpose, KeyPurpose::Encryption);
+    }
+
+    #[tokio::test]
+    async fn encrypt_decrypt_round_trip_and_wrong_key_fails() {
+        let store = InMemoryKeyStore::new();
+        let key = store
+            .generate_key(KeyAlgorithm::Ed25519, KeyPurpose::Encryption, metadata(None))
+            .await
+            .unwrap();
+        let other = store
+            .generate_key(KeyAlgorithm::Ed25519, KeyPurpose::Encryption, metadata(None))
+            .await
+            .unwrap();
+
+        let ciphertext = store.encrypt(&key, b"seed backup").await.unwrap();
+        assert_ne!(&ciphertext[NONCE_LEN..], b"seed backup");
+        assert_eq!(store.decrypt(&key, &ciphertext).await.unwrap(), b"seed backup");
+        assert!(matches!(
+            store.decrypt(&other, &ciphertext).await,
+            Err(KeyStoreError::CryptoError(_))
+        ));
+
+        let signing = store
+            .generate_key(KeyAlgorithm::Ed25519, KeyPurpose::Signing, metadata(None))
+            .await
+            .unwrap();
+        assert!(matches!(
+            store.encrypt(&signing, b"x").await,
+            Err(KeyStoreError::WrongKeyPurpose { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn short_ciphertext_and_repeated_nonce_are_rejected() {
+        let store = InMemoryKeyStore::with_nonce_tracking();
+        let key = store
+            .generate_key(KeyAlgorithm::Ed25519, KeyPurpose::Encryption, metadata(None))
+            .await
+            .unwrap();
+
+        let short = [0u8; NONCE_LEN + TAG_LEN - 1];
+        assert!(matches!(
+            store.decrypt(&key, &short).await,
+            Err(KeyStoreError::CryptoError(ref m)) if m.contains("too short")
+        ));
+
+        let nonce = [7u8; NONCE_LEN];
+        let first = store.encrypt_with_nonce(&key, nonce, b"a").await.unwrap();
+        assert_eq!(store.decrypt(&key, &first).await.unwrap(), b"a");
+        assert!(matches!(
+            store.encrypt_with_nonce(&key, nonce, b"b").await,
+            Err(KeyStoreError::NonceReuse(_))
+        ));
+        assert!(store.encrypt(&key, b"c").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn exists_and_count_track_keys() {
+        let store = InMemoryKeyStore::new();
+        assert!(!store.exists(&KeyFingerprint([0x42; 32])).await.unwrap());
+        assert_eq!(store.count().await.unwrap(), 0);
+
+        let a = store
+            .generate_key(KeyAlgorithm::Ed25519, KeyPurpose::Signing, metadata(None))
+            .await
+            .unwrap();
+        let b = store
+            .generate_key(KeyAlgorithm::EcdsaP256, KeyPurpose::Signing, metadata(None))
+            .await
+            .unwrap();
+        assert!(store.exists(&a).await.unwrap());
+        assert_eq!(store.count().await.unwrap(), 2);
+
+        store.delete_key(&b).await.unwrap();
+        assert!(!store.exists(&b).await.unwrap());
+        assert_eq!(store.count().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn lists_keys_by_tag() {
+        let store = InMemoryKeyStore::new();
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            ids.push(
+                store
+                    .generate_key(KeyAlgorithm::Ed25519, KeyPurpose::Signing, metadata(None))
+                    .await
+                    .unwrap(),
+            );
+        }
+        store.set_tag(&ids[0], "wallet", "treasury").await.unwrap();
+        store.set_tag(&ids[2], "wallet", "treasury").await.unwrap();
+        store.set_tag(&ids[1], "wallet", "ops").await.unwrap();
+
+        let mut tagged: Vec<KeyFingerprint> = store
+            .list_keys_by_tag("wallet", "treasury")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|k| k.fingerprint)
+            .collect();
+        tagged.sort_by_key(|f| f.0);
+        let mut expected = vec![ids[0].clone(), ids[2].clone()];
+        expected.sort_by_key(|f| f.0);
+        assert_eq!(tagged, expected);
+    }
+}
@@ -55,12 +55,76 @@ pub struct KeyInfo {
     pub metadata: KeyMetadata,
 }
 
+impl KeyMetadata {
+    pub fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+impl KeyInfo {
+    /// Whether the key's `expires_at` is at or before `now`. Keys without expiry never expire.
+    pub fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.metadata.is_expired(now)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Signature {
     pub algorithm: KeyAlgorithm,
     pub bytes: Vec<u8>,
 }
 
+/// Verify `signature` over `data` against a raw public key (Ed25519 bytes or SEC1-encoded
+/// ECDSA point), without a key store. A signature of another algorithm does not verify.
+pub fn verify_signature(
+    algorithm: KeyAlgorithm,
+    public_key: &[u8],
+    data: &[u8],
+    signature: &Signature,
+) -> Result<bool, KeyStoreError> {
+    if signature.algorithm != algorithm {
+        return Ok(false);
+    }
+    let ok = match algorithm {
+        KeyAlgorithm::Ed25519 => {
+            use ed25519_dalek::{Signature as EdSig, Verifier, VerifyingKey};
+            let key_bytes: &[u8; 32] = public_key.try_into().map_err(|_| {
+                KeyStoreError::InvalidKeyFormat("Invalid Ed25519 public key length".into())
+            })?;
+            let k = VerifyingKey::from_bytes(key_bytes)
+                .map_err(|e| KeyStoreError::InvalidKeyFormat(e.to_string()))?;
+            let sig = EdSig::from_bytes(
+                signature.bytes.as_slice().try_into().map_err(|_| {
+                    KeyStoreError::InvalidKeyFormat("Invalid Ed25519 signature length".into())
+                })?,
+            );
+            k.verify(data, &sig).is_ok()
+        }
+        KeyAlgorithm::EcdsaP256 => {
+            use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+            let k = VerifyingKey::from_sec1_bytes(public_key)
+                .map_err(|e| KeyStoreError::CryptoError(e.to_string()))?;
+            let sig = Signature::from_slice(&signature.bytes)
+                .map_err(|e| KeyStoreError::CryptoError(e.to_string()))?;
+            k.verify(data, &sig).is_ok()
+        }
+        KeyAlgorithm::EcdsaSecp256k1 => {
+            use k256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+            let k = VerifyingKey::from_sec1_bytes(public_key)
+                .map_err(|e| KeyStoreError::CryptoError(e.to_string()))?;
+            let sig = Signature::from_slice(&signature.bytes)
+                .map_err(|e| KeyStoreError::CryptoError(e.to_string()))?;
+            k.verify(data, &sig).is_ok()
+        }
+        KeyAlgorithm::X25519 => {
+            return Err(KeyStoreError::InvalidKeyFormat(
+                "X25519 is for key exchange, not signing".into(),
+            ));
+        }
+    };
+    Ok(ok)
+}
+
 #[derive(Debug, Error)]
 pub enum KeyStoreError {
     #[error("Key not found: {0}")]
@@ -75,6 +139,18 @@ pub enum KeyStoreError {
     StorageError(String),
     #[error("Access denied: {0}")]
     AccessDenied(String),
+    #[error("Key expired: {0}")]
+    KeyExpired(String),
+    #[error("Operation not supported by this key store: {0}")]
+    UnsupportedOperation(&'static str),
+    #[error("Nonce reuse refused for key {0}")]
+    NonceReuse(String),
+    #[error("Key {fingerprint} has purpose {actual:?}, cannot be used for {operation}")]
+    WrongKeyPurpose {
+        fingerprint: String,
+        actual: KeyPurpose,
+        operation: &'static str,
+    },
 }
 
 /// Key store trait for secure key management.
@@ -110,9 +186,128 @@ pub trait KeyStore: Send + Sync {
         signature: &Signature,
     ) -> Result<bool, KeyStoreError>;
 
+    /// Encrypt with an `Encryption`-purpose key (AES-256-GCM under a key derived from it).
+    /// Output is `nonce || ciphertext || tag`. Stores without encryption support keep the
+    /// default, which fails with [`KeyStoreError::UnsupportedOperation`].
+    async fn encrypt(
+        &self,
+        _fingerprint: &KeyFingerprint,
+        _plaintext: &[u8],
+    ) -> Result<Vec<u8>, KeyStoreError> {
+        Err(KeyStoreError::UnsupportedOperation("encrypt"))
+    }
+
+    /// Decrypt output of [`KeyStore::encrypt`] made with the same key.
+    async fn decrypt(
+        &self,
+        _fingerprint: &KeyFingerprint,
+        _ciphertext: &[u8],
+    ) -> Result<Vec<u8>, KeyStoreError> {
+        Err(KeyStoreError::UnsupportedOperation("decrypt"))
+    }
+
     async fn delete_key(&self, fingerprint: &KeyFingerprint) -> Result<(), KeyStoreError>;
 
     async fn list_keys(&self) -> Result<Vec<KeyInfo>, KeyStoreError>;
 
     async fn get_key_info(&self, fingerprint: &KeyFingerprint) -> Result<KeyInfo, KeyStoreError>;
+
+    async fn exists(&self, fingerprint: &KeyFingerprint) -> Result<bool, KeyStoreError> {
+        match self.get_key_info(fingerprint).await {
+            Ok(_) => Ok(true),
+            Err(KeyStoreError::KeyNotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn count(&self) -> Result<usize, KeyStoreError> {
+        Ok(self.list_keys().await?.len())
+    }
+
+    /// Set (or overwrite) a metadata tag on a key. Stores with immutable metadata keep the
+    /// default, which fails with [`KeyStoreError::UnsupportedOperation`].
+    async fn set_tag(
+        &self,
+        _fingerprint: &KeyFingerprint,
+        _key: &str,
+        _value: &str,
+    ) -> Result<(), KeyStoreError> {
+        Err(KeyStoreError::UnsupportedOperation("set_tag"))
+    }
+
+    /// Keys whose metadata has tag `key` equal to `value`.
+    async fn list_keys_by_tag(&self, key: &str, value: &str) -> Result<Vec<KeyInfo>, KeyStoreError> {
+        let mut keys = self.list_keys().await?;
+        keys.retain(|k| k.metadata.tags.get(key).map(String::as_str) == Some(value));
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A store implementing only the required methods, as an external implementor would.
+    struct MinimalStore;
+
+    #[async_trait]
+    impl KeyStore for MinimalStore {
+        async fn generate_key(
+            &self,
+            _: KeyAlgorithm,
+            _: KeyPurpose,
+            _: KeyMetadata,
+        ) -> Result<KeyFingerprint, KeyStoreError> {
+            Err(KeyStoreError::UnsupportedOperation("generate_key"))
+        }
+        async fn import_key(
+            &self,
+            _: &[u8],
+            _: KeyAlgorithm,
+            _: KeyPurpose,
+            _: KeyMetadata,
+        ) -> Result<KeyFingerprint, KeyStoreError> {
+            Err(KeyStoreError::UnsupportedOperation("import_key"))
+        }
+        async fn export_public_key(&self, fp: &KeyFingerprint) -> Result<Vec<u8>, KeyStoreError> {
+            Err(KeyStoreError::KeyNotFound(fp.to_hex()))
+        }
+        async fn sign(&self, fp: &KeyFingerprint, _: &[u8]) -> Result<Signature, KeyStoreError> {
+            Err(KeyStoreError::KeyNotFound(fp.to_hex()))
+        }
+        async fn verify(
+            &self,
+            fp: &KeyFingerprint,
+            _: &[u8],
+            _: &Signature,
+        ) -> Result<bool, KeyStoreError> {
+            Err(KeyStoreError::KeyNotFound(fp.to_hex()))
+        }
+        async fn delete_key(&self, fp: &KeyFingerprint) -> Result<(), KeyStoreError> {
+            Err(KeyStoreError::KeyNotFound(fp.to_hex()))
+        }
+        async fn list_keys(&self) -> Result<Vec<KeyInfo>, KeyStoreError> {
+            Ok(Vec::new())
+        }
+        async fn get_key_info(&self, fp: &KeyFingerprint) -> Result<KeyInfo, KeyStoreError> {
+            Err(KeyStoreError::KeyNotFound(fp.to_hex()))
+        }
+    }
+
+    #[tokio::test]
+    async fn optional_capabilities_default_to_unsupported() {
+        let fp = KeyFingerprint([0; 32]);
+        assert!(matches!(
+            MinimalStore.encrypt(&fp, b"data").await,
+            Err(KeyStoreError::UnsupportedOperation("encrypt"))
+        ));
+        assert!(matches!(
+            MinimalStore.decrypt(&fp, b"data").await,
+            Err(KeyStoreError::UnsupportedOperation("decrypt"))
+        ));
+        assert!(matches!(
+            MinimalStore.set_tag(&fp, "env", "prod").await,
+            Err(KeyStoreError::UnsupportedOperation("set_tag"))
+        ));
+    }
 }
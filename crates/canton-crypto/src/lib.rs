@@ -3,9 +3,11 @@
 
 pub mod keystore;
 pub mod keys;
+pub mod merkle;
 
 pub use keystore::{
     KeyFingerprint, KeyInfo, KeyMetadata, KeyStore, KeyStoreError, Signature,
-    InMemoryKeyStore,
+    InMemoryKeyStore, verify_signature,
 };
 pub use keys::{KeyAlgorithm, KeyPurpose};
+pub use merkle::{MerkleProof, MerkleTree, ProofElement};
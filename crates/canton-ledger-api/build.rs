@@ -13,8 +13,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    // Server traits exist only for the in-process stubs the unit tests run against.
     tonic_build::configure()
-        .build_server(false)
+        .build_server(true)
+        .server_mod_attribute(".", "#[cfg(test)]")
         .build_client(true)
         .include_file("generated.rs")
         .compile_protos(
@@ -25,6 +27,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 v2_dir.join("update_service.proto"),
                 v2_dir.join("state_service.proto"),
                 v2_dir.join("admin/party_management_service.proto"),
+                v2_dir.join("admin/package_management_service.proto"),
                 v2_dir.join("package_service.proto"),
                 v2_dir.join("version_service.proto"),
             ],
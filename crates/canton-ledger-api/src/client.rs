@@ -1,14 +1,36 @@
 //! Ledger API client — connects to Canton/Daml participant and exposes Ledger API v2 services.
 //! Only compiled when proto files are present (see proto/README.md).
 
-use canton_core::{error::*, types::LedgerOffset};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use canton_core::traits::{FromProto, ToProto};
+use canton_core::types::{
+    Commands, Completion, CreatedEvent, Filters, LedgerOffset, OffsetValue, PartyDetails, PartyId,
+    TransactionFilter,
+};
+use canton_core::error::*;
+use canton_observability::{LedgerProbe, MetricsRecorder};
+use futures::{Stream, StreamExt};
 use tonic::transport::Channel;
-use tonic::Status;
+use tonic::{Request, Status};
+use tracing::Instrument;
 
+use crate::generated::com::daml::ledger::api::v2::admin::{
+    package_management_service_client::PackageManagementServiceClient,
+    party_management_service_client::PartyManagementServiceClient, AllocatePartyRequest,
+    GetPartiesRequest, ListKnownPartiesRequest, ObjectMeta, UploadDarFileRequest,
+};
 use crate::generated::com::daml::ledger::api::v2::{
+    command_completion_service_client::CommandCompletionServiceClient,
     command_submission_service_client::CommandSubmissionServiceClient,
+    completion_stream_response::CompletionResponse,
+    get_active_contracts_response::ContractEntry,
+    package_service_client::PackageServiceClient,
     state_service_client::StateServiceClient,
-    GetLedgerEndRequest, SubmitRequest,
+    CompletionStreamRequest, CompletionStreamResponse, GetActiveContractsRequest,
+    GetActiveContractsResponse, GetLedgerEndRequest, ListPackagesRequest, SubmitRequest,
 };
 
 /// Ledger API v2 client. Holds gRPC channel and service stubs.
@@ -19,8 +41,19 @@ pub struct LedgerClient {
     ledger_id: String,
     state: StateServiceClient<Channel>,
     command_submission: CommandSubmissionServiceClient<Channel>,
+    command_completion: CommandCompletionServiceClient<Channel>,
+    party_management: PartyManagementServiceClient<Channel>,
+    packages: PackageServiceClient<Channel>,
+    package_management: PackageManagementServiceClient<Channel>,
+    user_id: String,
+    metrics: Option<Arc<MetricsRecorder>>,
+    deadline: Option<Duration>,
+    redact_sensitive: bool,
 }
 
+/// Histogram of end-to-end submit latency, labeled by `operation` and `success`.
+pub const SUBMIT_DURATION_METRIC: &str = "ledger_submit_duration_seconds";
+
 impl LedgerClient {
     /// Connect to a participant and create a Ledger API client.
     /// `endpoint`: e.g. `"http://localhost:5011"` or `"https://participant.example.com"`.
@@ -39,15 +72,73 @@ impl LedgerClient {
                 cause: Some(Box::new(e)),
                 backtrace: std::backtrace::Backtrace::capture(),
             })?;
-        let ledger_id = ledger_id.into();
+        Ok(Self::from_channel(channel, ledger_id))
+    }
+
+    /// Connect using a full [`CantonConfig`](canton_core::config::CantonConfig): timeouts,
+    /// keep-alive and TLS (including an mTLS client identity) come from the config.
+    pub async fn connect_with_config(
+        config: &canton_core::config::CantonConfig,
+        ledger_id: impl Into<String>,
+    ) -> SdkResult<Self> {
+        let channel = canton_transport::connect(config).await?;
+        Ok(Self::from_channel(channel, ledger_id))
+    }
+
+    /// Create a client over an already configured channel (custom TLS, lazy connect, ...).
+    pub fn from_channel(channel: Channel, ledger_id: impl Into<String>) -> Self {
         let state = StateServiceClient::new(channel.clone());
         let command_submission = CommandSubmissionServiceClient::new(channel.clone());
-        Ok(Self {
+        let command_completion = CommandCompletionServiceClient::new(channel.clone());
+        let party_management = PartyManagementServiceClient::new(channel.clone());
+        let packages = PackageServiceClient::new(channel.clone());
+        let package_management = PackageManagementServiceClient::new(channel.clone());
+        Self {
             channel,
-            ledger_id,
+            ledger_id: ledger_id.into(),
             state,
             command_submission,
-        })
+            command_completion,
+            party_management,
+            packages,
+            package_management,
+            user_id: String::new(),
+            metrics: None,
+            deadline: None,
+            redact_sensitive: false,
+        }
+    }
+
+    /// Record submit latency into `recorder` under [`SUBMIT_DURATION_METRIC`].
+    pub fn with_metrics(mut self, recorder: Arc<MetricsRecorder>) -> Self {
+        recorder.register_histogram(
+            SUBMIT_DURATION_METRIC,
+            "End-to-end ledger submit latency in seconds",
+            canton_observability::metrics::DEFAULT_BUCKETS,
+        );
+        self.metrics = Some(recorder);
+        self
+    }
+
+    /// Copy of this client whose calls carry a per-request gRPC deadline, overriding the
+    /// channel's `request_timeout`. Calls past the deadline fail with [`SdkError::Timeout`].
+    pub fn with_deadline(&self, deadline: Duration) -> Self {
+        let mut client = self.clone();
+        client.deadline = Some(deadline);
+        client
+    }
+
+    /// User id sent with user-scoped requests (completion streams). May stay empty when the
+    /// participant derives it from a user access token.
+    pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = user_id.into();
+        self
+    }
+
+    /// Replace party and user ids in log spans with a placeholder.
+    pub fn with_redacted_logging(mut self, redact: bool) -> Self {
+        self.redact_sensitive = redact;
+        self
     }
 
     /// Ledger identifier for this connection.
@@ -58,30 +149,353 @@ impl LedgerClient {
     /// Get the current ledger end offset. Subscriptions started with this offset
     /// will receive events after this call.
     pub async fn get_ledger_end(&mut self) -> SdkResult<LedgerOffset> {
-        let request = GetLedgerEndRequest {};
+        let request = self.request(GetLedgerEndRequest {});
         let response = self
             .state
             .get_ledger_end(request)
             .await
-            .map_err(grpc_status_to_sdk_error)?;
+            .map_err(|s| self.status_to_error(s))?;
         let offset = response.into_inner().offset;
         Ok(LedgerOffset::absolute(offset.to_string()))
     }
 
-    /// Submit commands to the ledger. Uses proto Commands (built from canton_core::types::Commands
-    /// via a conversion layer when needed).
+    /// Submit SDK [`Commands`]: converted to the proto request, with this client's user id
+    /// filling an empty `application_id`.
+    pub async fn submit_commands(&mut self, commands: &Commands) -> SdkResult<()> {
+        let mut proto = commands.to_proto()?;
+        if proto.user_id.is_empty() {
+            proto.user_id = self.user_id.clone();
+        }
+        self.submit(proto).await
+    }
+
+    /// Submit proto Commands to the ledger; see [`LedgerClient::submit_commands`] for SDK types.
     pub async fn submit(&mut self, commands: crate::generated::com::daml::ledger::api::v2::Commands) -> SdkResult<()> {
-        let request = SubmitRequest {
+        let span = submit_span(&commands, self.redact_sensitive);
+        let request = self.request(SubmitRequest {
             commands: Some(commands),
-        };
-        self.command_submission
+        });
+        let start = Instant::now();
+        let result = self
+            .command_submission
             .submit(request)
+            .instrument(span.clone())
             .await
-            .map_err(grpc_status_to_sdk_error)?;
+            .map(|_| ())
+            .map_err(|s| self.status_to_error(s));
+        if let Err(ref e) = result {
+            span.in_scope(|| tracing::warn!(error = %e, "submit failed"));
+        }
+        self.record_duration("submit", start, result.is_ok());
+        result
+    }
+
+    /// Active contracts visible to `party` under `filters`, together with the offset the
+    /// snapshot is valid at. Subscribing to updates from that offset (exclusive) continues
+    /// the snapshot without gaps or duplicates.
+    pub async fn acs_snapshot_with_offset(
+        &self,
+        party: &PartyId,
+        filters: &Filters,
+    ) -> SdkResult<(Vec<CreatedEvent>, LedgerOffset)> {
+        let mut client = self.clone();
+        let offset = client.get_ledger_end().await?;
+        let filter = TransactionFilter {
+            filters_by_party: [(party.to_string(), filters.clone())].into(),
+        };
+        let request = client.request(GetActiveContractsRequest {
+            active_at_offset: absolute_offset(&offset)?,
+            event_format: Some(filter.to_proto()?),
+        });
+        let responses = client
+            .state
+            .get_active_contracts(request)
+            .await
+            .map_err(|s| client.status_to_error(s))?
+            .into_inner();
+        Ok((active_contracts(responses).await?, offset))
+    }
+
+    /// Stream completions of commands submitted by this client's user on behalf of `party_id`,
+    /// starting after `offset`. Match them to submissions by `command_id`.
+    pub async fn completion_stream_for_party(
+        &self,
+        party_id: &PartyId,
+        offset: &LedgerOffset,
+    ) -> SdkResult<impl Stream<Item = SdkResult<Completion>> + Send + 'static> {
+        let mut client = self.clone();
+        let begin_exclusive = match &offset.value {
+            OffsetValue::Begin => 0,
+            OffsetValue::End => absolute_offset(&client.get_ledger_end().await?)?,
+            OffsetValue::Absolute(_) => absolute_offset(offset)?,
+        };
+        let request = client.request(CompletionStreamRequest {
+            user_id: client.user_id.clone(),
+            parties: vec![party_id.to_string()],
+            begin_exclusive,
+        });
+        let responses = client
+            .command_completion
+            .completion_stream(request)
+            .await
+            .map_err(|s| client.status_to_error(s))?
+            .into_inner();
+        Ok(completions(responses))
+    }
+
+    /// Allocate a new party on the participant. `hint` becomes the party id prefix; the
+    /// participant picks one when `None`. `display_name` is kept as a local annotation.
+    pub async fn allocate_party(
+        &self,
+        hint: Option<&str>,
+        display_name: Option<&str>,
+    ) -> SdkResult<PartyDetails> {
+        let mut client = self.clone();
+        let request = client.request(allocate_party_request(hint, display_name, &client.user_id));
+        let details = client
+            .party_management
+            .allocate_party(request)
+            .await
+            .map_err(|s| client.status_to_error(s))?
+            .into_inner()
+            .party_details
+            .ok_or_else(|| SdkError::Serialization("AllocatePartyResponse without party_details".into()))?;
+        PartyDetails::from_proto(details)
+    }
+
+    /// All parties known to the participant, following pagination to the end.
+    pub async fn list_parties(&self) -> SdkResult<Vec<PartyDetails>> {
+        let mut client = self.clone();
+        let mut parties = Vec::new();
+        let mut page_token = String::new();
+        loop {
+            let request = client.request(ListKnownPartiesRequest {
+                page_token: std::mem::take(&mut page_token),
+                ..Default::default()
+            });
+            let response = client
+                .party_management
+                .list_known_parties(request)
+                .await
+                .map_err(|s| client.status_to_error(s))?
+                .into_inner();
+            for details in response.party_details {
+                parties.push(PartyDetails::from_proto(details)?);
+            }
+            if response.next_page_token.is_empty() {
+                return Ok(parties);
+            }
+            page_token = response.next_page_token;
+        }
+    }
+
+    /// Details of one party, or `None` if the participant does not know it.
+    pub async fn get_party_details(&self, party: &PartyId) -> SdkResult<Option<PartyDetails>> {
+        let mut client = self.clone();
+        let request = client.request(GetPartiesRequest {
+            parties: vec![party.to_string()],
+            ..Default::default()
+        });
+        let response = client
+            .party_management
+            .get_parties(request)
+            .await
+            .map_err(|s| client.status_to_error(s))?
+            .into_inner();
+        response
+            .party_details
+            .into_iter()
+            .find(|d| d.party == party.as_str())
+            .map(PartyDetails::from_proto)
+            .transpose()
+    }
+
+    /// Ids of all packages known to the participant.
+    pub async fn list_packages(&self) -> SdkResult<Vec<String>> {
+        let mut client = self.clone();
+        let request = client.request(ListPackagesRequest {});
+        Ok(client
+            .packages
+            .list_packages(request)
+            .await
+            .map_err(|s| client.status_to_error(s))?
+            .into_inner()
+            .package_ids)
+    }
+
+    /// Upload a DAR and vet its packages. Returns the ids of packages that became known
+    /// through this upload (UploadDarFile itself reports none), so re-uploading a DAR the
+    /// participant already has yields an empty list.
+    ///
+    /// The ids are a best-effort diff of `list_packages` taken before and after the upload:
+    /// packages another client uploads in between are reported too, and packages removed in
+    /// between are not noticed. Callers that need the exact set should read the DAR manifest.
+    pub async fn upload_dar(&self, dar_bytes: &[u8]) -> SdkResult<Vec<String>> {
+        validate_dar(dar_bytes)?;
+        let before = self.list_packages().await?;
+        let mut client = self.clone();
+        let request = client.request(UploadDarFileRequest {
+            dar_file: dar_bytes.to_vec(),
+            ..Default::default()
+        });
+        client
+            .package_management
+            .upload_dar_file(request)
+            .await
+            .map_err(|s| client.status_to_error(s))?;
+        let after = self.list_packages().await?;
+        Ok(new_package_ids(&before, after))
+    }
+
+    fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        if let Some(deadline) = self.deadline {
+            request.set_timeout(deadline);
+        }
+        request
+    }
+
+    /// Like [`grpc_status_to_sdk_error`], but reports the per-request deadline on timeouts.
+    fn status_to_error(&self, status: Status) -> SdkError {
+        match (grpc_status_to_sdk_error(status), self.deadline) {
+            (SdkError::Timeout { operation, .. }, Some(duration)) => SdkError::Timeout { duration, operation },
+            (err, _) => err,
+        }
+    }
+
+    fn record_duration(&self, operation: &str, start: Instant, success: bool) {
+        if let Some(metrics) = &self.metrics {
+            let success = if success { "true" } else { "false" };
+            metrics.observe_histogram(
+                SUBMIT_DURATION_METRIC,
+                &[("operation", operation), ("success", success)],
+                start.elapsed().as_secs_f64(),
+            );
+        }
+    }
+}
+
+fn allocate_party_request(hint: Option<&str>, display_name: Option<&str>, user_id: &str) -> AllocatePartyRequest {
+    let local_metadata = display_name.map(|name| ObjectMeta {
+        annotations: [(PartyDetails::DISPLAY_NAME_ANNOTATION.to_string(), name.to_string())].into(),
+        ..Default::default()
+    });
+    AllocatePartyRequest {
+        party_id_hint: hint.unwrap_or_default().to_string(),
+        local_metadata,
+        user_id: user_id.to_string(),
+        ..Default::default()
+    }
+}
+
+/// DARs are zip archives; reject anything without the local file header magic.
+fn validate_dar(dar_bytes: &[u8]) -> SdkResult<()> {
+    const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+    if dar_bytes.starts_with(ZIP_MAGIC) {
         Ok(())
+    } else {
+        Err(SdkError::Validation {
+            field: "dar".into(),
+            message: "invalid DAR: not a zip archive".into(),
+        })
+    }
+}
+
+fn new_package_ids(before: &[String], after: Vec<String>) -> Vec<String> {
+    let known: std::collections::HashSet<&String> = before.iter().collect();
+    after.into_iter().filter(|id| !known.contains(id)).collect()
+}
+
+fn absolute_offset(offset: &LedgerOffset) -> SdkResult<i64> {
+    match &offset.value {
+        OffsetValue::Absolute(s) => s.parse().map_err(|_| SdkError::Validation {
+            field: "offset".into(),
+            message: format!("not an absolute ledger offset: {:?}", s),
+        }),
+        other => Err(SdkError::Validation {
+            field: "offset".into(),
+            message: format!("expected an absolute offset, got {:?}", other),
+        }),
+    }
+}
+
+/// Collect created events of active contracts; entries for contracts mid-reassignment
+/// are skipped.
+async fn active_contracts(
+    responses: impl Stream<Item = Result<GetActiveContractsResponse, Status>>,
+) -> SdkResult<Vec<CreatedEvent>> {
+    futures::pin_mut!(responses);
+    let mut events = Vec::new();
+    while let Some(response) = responses.next().await {
+        let response = response.map_err(grpc_status_to_sdk_error)?;
+        if let Some(ContractEntry::ActiveContract(contract)) = response.contract_entry {
+            if let Some(event) = contract.created_event {
+                events.push(CreatedEvent::from_proto(event)?);
+            }
+        }
+    }
+    Ok(events)
+}
+
+/// Convert a raw completion stream into SDK completions, dropping offset checkpoints.
+fn completions(
+    responses: impl Stream<Item = Result<CompletionStreamResponse, Status>> + Send + 'static,
+) -> impl Stream<Item = SdkResult<Completion>> + Send + 'static {
+    responses.filter_map(|response| async move {
+        match response {
+            Ok(CompletionStreamResponse {
+                completion_response: Some(CompletionResponse::Completion(completion)),
+            }) => Some(Completion::from_proto(completion)),
+            Ok(_) => None,
+            Err(status) => Some(Err(grpc_status_to_sdk_error(status))),
+        }
+    })
+}
+
+/// The channel connects and reconnects lazily, so there is no local connection state to report:
+/// `is_connected` keeps the default and readiness is decided by the `fetch_ledger_id` round-trip.
+#[async_trait]
+impl LedgerProbe for LedgerClient {
+    /// Round-trips via GetLedgerEnd (Ledger API v2 has no ledger identity service).
+    async fn fetch_ledger_id(&self) -> SdkResult<String> {
+        let mut client = self.clone();
+        client.get_ledger_end().await?;
+        Ok(client.ledger_id)
     }
 }
 
+/// Placeholder for identifiers hidden by [`LedgerClient::with_redacted_logging`].
+const REDACTED: &str = "<redacted>";
+
+/// Span for one submission, with fields log aggregators can filter on.
+fn submit_span(commands: &crate::generated::com::daml::ledger::api::v2::Commands, redact: bool) -> tracing::Span {
+    let (party_id, user_id) = if redact {
+        (REDACTED.to_string(), REDACTED)
+    } else {
+        (commands.act_as.join(","), commands.user_id.as_str())
+    };
+    tracing::info_span!(
+        "ledger_submit",
+        party_id = %party_id,
+        user_id = %user_id,
+        command_id = %commands.command_id,
+        command_count = commands.commands.len(),
+    )
+}
+
+/// tonic enforces a request's `grpc-timeout` on the client side too, reporting expiry as
+/// `Cancelled` with a [`tonic::TimeoutExpired`] source rather than `DeadlineExceeded`.
+fn is_client_timeout(status: &Status) -> bool {
+    let mut source = std::error::Error::source(status);
+    while let Some(err) = source {
+        if err.is::<tonic::TimeoutExpired>() {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
 fn grpc_status_to_sdk_error(status: Status) -> SdkError {
     let message = status.message().to_string();
     let code = status.code();
@@ -91,11 +505,15 @@ fn grpc_status_to_sdk_error(status: Status) -> SdkError {
             cause: None,
             backtrace: std::backtrace::Backtrace::capture(),
         }
-    } else if code == tonic::Code::DeadlineExceeded {
+    } else if code == tonic::Code::DeadlineExceeded || is_client_timeout(&status) {
         SdkError::Timeout {
             duration: std::time::Duration::from_secs(0),
             operation: message,
         }
+    } else if let Some(err) = CantonErrorCode::parse(&message).and_then(|_| {
+        SdkError::from_completion_status(&canton_core::types::CompletionStatus::from_grpc(code as i32, &message))
+    }) {
+        err
     } else {
         SdkError::Internal {
             message: format!("grpc {}: {}", code, message),
@@ -107,6 +525,18 @@ fn grpc_status_to_sdk_error(status: Status) -> SdkError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generated::com::daml::ledger::api::v2::admin::{
+        self,
+        package_management_service_server::{PackageManagementService, PackageManagementServiceServer},
+    };
+    use crate::generated::com::daml::ledger::api::v2::package_service_server::{PackageService, PackageServiceServer};
+    use crate::generated::com::daml::ledger::api::v2::state_service_server::{StateService, StateServiceServer};
+    use crate::generated::com::daml::ledger::api::v2::{
+        GetConnectedSynchronizersRequest, GetConnectedSynchronizersResponse, GetLatestPrunedOffsetsRequest,
+        GetLatestPrunedOffsetsResponse, GetLedgerEndResponse, GetPackageRequest, GetPackageResponse,
+        GetPackageStatusRequest, GetPackageStatusResponse, ListPackagesResponse, ListVettedPackagesRequest,
+        ListVettedPackagesResponse,
+    };
     use canton_core::types::OffsetValue;
 
     #[test]
@@ -123,6 +553,400 @@ mod tests {
         assert!(matches!(err, SdkError::Timeout { .. }));
     }
 
+    #[test]
+    fn grpc_canton_error_keeps_error_code() {
+        let status = Status::not_found("CONTRACT_NOT_FOUND(11,abc123): Contract could not be found");
+        let err = grpc_status_to_sdk_error(status);
+        assert_eq!(err.code(), Some("CONTRACT_NOT_FOUND"));
+        assert_eq!(err.correlation_id(), Some("abc123"));
+    }
+
+    #[test]
+    fn grpc_ok_with_canton_message_does_not_panic() {
+        let status = Status::new(tonic::Code::Ok, "CONTRACT_NOT_FOUND(11,abc123): Contract could not be found");
+        let err = grpc_status_to_sdk_error(status);
+        assert!(matches!(err, SdkError::Internal { ref message, .. } if message.contains("CONTRACT_NOT_FOUND")));
+    }
+
+    #[tokio::test]
+    async fn submit_records_one_latency_observation() {
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let recorder = Arc::new(MetricsRecorder::new());
+        let mut client = LedgerClient::from_channel(channel, "ledger").with_metrics(recorder.clone());
+
+        assert!(client.submit(Default::default()).await.is_err());
+
+        let text = recorder.render_prometheus();
+        assert!(text.contains(
+            "ledger_submit_duration_seconds_count{operation=\"submit\",success=\"false\"} 1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn deadline_exceeded_reports_per_request_deadline() {
+        // A participant that accepts the connection but never answers.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        let channel = Channel::from_shared(format!("http://{}", addr)).unwrap().connect_lazy();
+        let client = LedgerClient::from_channel(channel, "ledger");
+
+        let start = Instant::now();
+        let err = client.with_deadline(Duration::from_millis(100)).get_ledger_end().await.unwrap_err();
+        assert!(matches!(err, SdkError::Timeout { duration, .. } if duration == Duration::from_millis(100)), "{}", err);
+        assert!(start.elapsed() < Duration::from_secs(2), "call outlived its deadline");
+    }
+
+    #[tokio::test]
+    async fn ledger_health_rests_on_the_round_trip() {
+        use canton_observability::{HealthCheck, HealthStatus, LedgerHealthCheck};
+
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let unreachable = LedgerClient::from_channel(channel, "ledger");
+        let check = LedgerHealthCheck::new(Arc::new(unreachable), Duration::from_secs(2));
+        assert_eq!(check.check().await.status, HealthStatus::Unhealthy);
+
+        let reachable = serve_state(StubState::default()).await;
+        let check = LedgerHealthCheck::new(Arc::new(reachable), Duration::from_secs(2));
+        let result = check.check().await;
+        assert_eq!(result.status, HealthStatus::Healthy, "{:?}", result.message);
+        assert_eq!(result.details["ledger_id"], "ledger");
+    }
+
+    #[tokio::test]
+    async fn completion_stream_yields_success_and_rejection() {
+        use crate::generated::com::daml::ledger::api::v2 as proto;
+        use crate::generated::google::rpc;
+        use canton_core::types::CompletionStatus;
+
+        let completion = |command_id: &str, status: Option<rpc::Status>| {
+            CompletionStreamResponse {
+                completion_response: Some(CompletionResponse::Completion(proto::Completion {
+                    command_id: command_id.into(),
+                    status,
+                    offset: 7,
+                    ..Default::default()
+                })),
+            }
+        };
+        let responses = futures::stream::iter(vec![
+            completion("cmd-ok", None),
+            CompletionStreamResponse { completion_response: None },
+            completion(
+                "cmd-rejected",
+                Some(rpc::Status {
+                    code: tonic::Code::NotFound as i32,
+                    message: "CONTRACT_NOT_FOUND(11,abc): gone".into(),
+                    details: vec![],
+                }),
+            ),
+        ])
+        .map(Ok);
+
+        let received: Vec<Completion> = completions(responses)
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].command_id, "cmd-ok");
+        assert!(received[0].is_success());
+        assert_eq!(received[1].command_id, "cmd-rejected");
+        assert!(matches!(
+            received[1].status,
+            CompletionStatus::ContractNotFound { code: 5, .. }
+        ));
+    }
+
+    #[test]
+    fn allocated_party_reflects_hint_and_display_name() {
+        let request = allocate_party_request(Some("alice"), Some("Alice"), "");
+        assert_eq!(request.party_id_hint, "alice");
+
+        // Participant echoes the hint as the party id prefix and keeps the metadata.
+        let response = admin::PartyDetails {
+            party: format!("{}::1220abcdef", request.party_id_hint),
+            is_local: true,
+            local_metadata: request.local_metadata.clone(),
+            identity_provider_id: String::new(),
+        };
+        let details = PartyDetails::from_proto(response).unwrap();
+        assert!(details.party.as_str().starts_with("alice::"));
+        assert!(details.is_local);
+        assert_eq!(details.display_name.as_deref(), Some("Alice"));
+        assert_eq!(details.identity_provider_id, None);
+
+        assert!(allocate_party_request(None, None, "").local_metadata.is_none());
+    }
+
+    #[tokio::test]
+    async fn upload_dar_rejects_non_zip_bytes() {
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let client = LedgerClient::from_channel(channel, "ledger");
+        let err = client.upload_dar(b"definitely not a dar").await.unwrap_err();
+        assert!(matches!(err, SdkError::Validation { ref field, .. } if field == "dar"));
+    }
+
+    #[test]
+    fn upload_reports_newly_known_package_ids() {
+        assert!(validate_dar(b"PK\x03\x04rest-of-archive").is_ok());
+        let before = vec!["pkg-a".to_string()];
+        let after = vec!["pkg-a".to_string(), "pkg-b".to_string(), "pkg-c".to_string()];
+        assert_eq!(new_package_ids(&before, after), vec!["pkg-b", "pkg-c"]);
+    }
+
+    /// In-process StateService: reports `ledger_end` and serves two active contracts,
+    /// remembering the offset each ACS request was pinned to.
+    #[derive(Default)]
+    struct StubState {
+        ledger_end: i64,
+        active_at: Arc<std::sync::Mutex<Vec<i64>>>,
+    }
+
+    #[tonic::async_trait]
+    impl StateService for StubState {
+        type GetActiveContractsStream = futures::stream::Iter<std::vec::IntoIter<Result<GetActiveContractsResponse, Status>>>;
+
+        async fn get_active_contracts(
+            &self,
+            request: Request<GetActiveContractsRequest>,
+        ) -> Result<tonic::Response<Self::GetActiveContractsStream>, Status> {
+            use crate::generated::com::daml::ledger::api::v2 as proto;
+
+            let offset = request.into_inner().active_at_offset;
+            self.active_at.lock().unwrap().push(offset);
+            let contract = |node_id: i32, id: &str| GetActiveContractsResponse {
+                workflow_id: String::new(),
+                contract_entry: Some(ContractEntry::ActiveContract(proto::ActiveContract {
+                    created_event: Some(proto::CreatedEvent {
+                        offset,
+                        node_id,
+                        contract_id: id.into(),
+                        template_id: Some(proto::Identifier {
+                            package_id: "pkg".into(),
+                            module_name: "Iou".into(),
+                            entity_name: "Iou".into(),
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })),
+            };
+            let responses = vec![Ok(contract(0, "00aa")), Ok(contract(1, "00bb"))];
+            Ok(tonic::Response::new(futures::stream::iter(responses)))
+        }
+
+        async fn get_connected_synchronizers(
+            &self,
+            _: Request<GetConnectedSynchronizersRequest>,
+        ) -> Result<tonic::Response<GetConnectedSynchronizersResponse>, Status> {
+            Err(Status::unimplemented("stub"))
+        }
+
+        async fn get_ledger_end(
+            &self,
+            _: Request<GetLedgerEndRequest>,
+        ) -> Result<tonic::Response<GetLedgerEndResponse>, Status> {
+            Ok(tonic::Response::new(GetLedgerEndResponse {
+                offset: self.ledger_end,
+            }))
+        }
+
+        async fn get_latest_pruned_offsets(
+            &self,
+            _: Request<GetLatestPrunedOffsetsRequest>,
+        ) -> Result<tonic::Response<GetLatestPrunedOffsetsResponse>, Status> {
+            Err(Status::unimplemented("stub"))
+        }
+    }
+
+    /// Serve `router` on an ephemeral local port and return a client connected to it.
+    async fn serve(router: tonic::transport::server::Router) -> LedgerClient {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(router.serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)));
+        let channel = Channel::from_shared(format!("http://{}", addr)).unwrap().connect().await.unwrap();
+        LedgerClient::from_channel(channel, "ledger")
+    }
+
+    async fn serve_state(state: StubState) -> LedgerClient {
+        serve(tonic::transport::Server::builder().add_service(StateServiceServer::new(state))).await
+    }
+
+    /// In-process PackageService + PackageManagementService sharing one package list; every
+    /// upload makes the participant know `pkg-new`.
+    #[derive(Clone, Default)]
+    struct StubPackages {
+        known: Arc<std::sync::Mutex<Vec<String>>>,
+        uploads: Arc<std::sync::Mutex<Vec<Vec<u8>>>>,
+    }
+
+    #[tonic::async_trait]
+    impl PackageService for StubPackages {
+        async fn list_packages(
+            &self,
+            _: Request<ListPackagesRequest>,
+        ) -> Result<tonic::Response<ListPackagesResponse>, Status> {
+            Ok(tonic::Response::new(ListPackagesResponse {
+                package_ids: self.known.lock().unwrap().clone(),
+            }))
+        }
+
+        async fn get_package(
+            &self,
+            _: Request<GetPackageRequest>,
+        ) -> Result<tonic::Response<GetPackageResponse>, Status> {
+            Err(Status::unimplemented("stub"))
+        }
+
+        async fn get_package_status(
+            &self,
+            _: Request<GetPackageStatusRequest>,
+        ) -> Result<tonic::Response<GetPackageStatusResponse>, Status> {
+            Err(Status::unimplemented("stub"))
+        }
+
+        async fn list_vetted_packages(
+            &self,
+            _: Request<ListVettedPackagesRequest>,
+        ) -> Result<tonic::Response<ListVettedPackagesResponse>, Status> {
+            Err(Status::unimplemented("stub"))
+        }
+    }
+
+    #[tonic::async_trait]
+    impl PackageManagementService for StubPackages {
+        async fn list_known_packages(
+            &self,
+            _: Request<admin::ListKnownPackagesRequest>,
+        ) -> Result<tonic::Response<admin::ListKnownPackagesResponse>, Status> {
+            Err(Status::unimplemented("stub"))
+        }
+
+        async fn upload_dar_file(
+            &self,
+            request: Request<UploadDarFileRequest>,
+        ) -> Result<tonic::Response<admin::UploadDarFileResponse>, Status> {
+            self.uploads.lock().unwrap().push(request.into_inner().dar_file);
+            let mut known = self.known.lock().unwrap();
+            if !known.iter().any(|id| id == "pkg-new") {
+                known.push("pkg-new".into());
+            }
+            Ok(tonic::Response::new(admin::UploadDarFileResponse {}))
+        }
+
+        async fn validate_dar_file(
+            &self,
+            _: Request<admin::ValidateDarFileRequest>,
+        ) -> Result<tonic::Response<admin::ValidateDarFileResponse>, Status> {
+            Err(Status::unimplemented("stub"))
+        }
+
+        async fn update_vetted_packages(
+            &self,
+            _: Request<admin::UpdateVettedPackagesRequest>,
+        ) -> Result<tonic::Response<admin::UpdateVettedPackagesResponse>, Status> {
+            Err(Status::unimplemented("stub"))
+        }
+    }
+
+    #[tokio::test]
+    async fn upload_dar_reports_packages_new_to_the_participant() {
+        let stub = StubPackages::default();
+        stub.known.lock().unwrap().push("pkg-a".into());
+        let client = serve(
+            tonic::transport::Server::builder()
+                .add_service(PackageServiceServer::new(stub.clone()))
+                .add_service(PackageManagementServiceServer::new(stub.clone())),
+        )
+        .await;
+
+        let dar = b"PK\x03\x04rest-of-archive";
+        assert_eq!(client.upload_dar(dar).await.unwrap(), vec!["pkg-new"]);
+        assert_eq!(stub.uploads.lock().unwrap().as_slice(), &[dar.to_vec()]);
+
+        // The participant already knows everything in the DAR the second time round.
+        assert!(client.upload_dar(dar).await.unwrap().is_empty());
+        assert_eq!(client.list_packages().await.unwrap(), vec!["pkg-a", "pkg-new"]);
+    }
+
+    #[tokio::test]
+    async fn acs_snapshot_collects_active_contracts_at_offset() {
+        let active_at = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let client = serve_state(StubState {
+            ledger_end: 42,
+            active_at: active_at.clone(),
+        })
+        .await;
+
+        let party = PartyId::new("alice::1220ab").unwrap();
+        let (events, offset) = client.acs_snapshot_with_offset(&party, &Filters { inclusive: None }).await.unwrap();
+
+        // The snapshot is pinned to the ledger end read first, and reports that offset so a
+        // follow-up update subscription starts exactly there.
+        assert_eq!(*active_at.lock().unwrap(), vec![42]);
+        assert!(matches!(offset.value, OffsetValue::Absolute(ref s) if s == "42"));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].contract_id.as_str(), "00bb");
+        assert_eq!(events[0].event_id, "42:0");
+    }
+
+    /// Records the fields of every span created while it is the default subscriber.
+    #[derive(Clone, Default)]
+    struct SpanFields(Arc<std::sync::Mutex<Vec<(String, String)>>>);
+
+    impl tracing::field::Visit for SpanFields {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.lock().unwrap().push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+
+    impl tracing::Subscriber for SpanFields {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            span.record(&mut self.clone());
+            tracing::span::Id::from_u64(1)
+        }
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+        fn event(&self, _: &tracing::Event<'_>) {}
+        fn enter(&self, _: &tracing::span::Id) {}
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[test]
+    fn submit_span_carries_filterable_fields() {
+        use crate::generated::com::daml::ledger::api::v2::{Command, Commands};
+        let commands = Commands {
+            act_as: vec!["alice::1220ab".into()],
+            user_id: "svc".into(),
+            command_id: "cmd-7".into(),
+            commands: vec![Command::default(), Command::default()],
+            ..Default::default()
+        };
+        let fields = |redact: bool| {
+            let captured = SpanFields::default();
+            tracing::subscriber::with_default(captured.clone(), || drop(submit_span(&commands, redact)));
+            let fields = captured.0.lock().unwrap().clone();
+            fields
+        };
+
+        let plain = fields(false);
+        for expected in [("party_id", "alice::1220ab"), ("user_id", "svc"), ("command_id", "cmd-7"), ("command_count", "2")] {
+            assert!(plain.contains(&(expected.0.to_string(), expected.1.to_string())), "{:?}", plain);
+        }
+        let redacted = fields(true);
+        assert!(redacted.contains(&("party_id".to_string(), REDACTED.to_string())));
+        assert!(redacted.contains(&("user_id".to_string(), REDACTED.to_string())));
+        assert!(redacted.contains(&("command_id".to_string(), "cmd-7".to_string())));
+    }
+
     #[test]
     fn ledger_offset_absolute_from_get_ledger_end_response() {
         let offset = LedgerOffset::absolute(42_i64.to_string());
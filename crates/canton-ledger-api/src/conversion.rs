@@ -0,0 +1,601 @@
+//! Conversions between Ledger API v2 proto messages and canton-core types.
+//! See research/08 §4.2.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use canton_core::error::{SdkError, SdkResult};
+use canton_core::traits::{FromProto, ToProto};
+use canton_core::types::{
+    Command, Commands, Completion, CompletionStatus, ContractId, CreatedEvent, DamlEnum, DamlRecord,
+    DamlValue, DamlVariant, DisclosedContract, Filters, Identifier, PartyDetails, PartyId, RecordField, TransactionFilter,
+};
+use chrono::{DateTime, Utc};
+use prost::Message;
+use rust_decimal::Decimal;
+
+use crate::generated::com::daml::ledger::api::v2 as proto;
+use proto::value::Sum;
+
+/// How to treat Ledger API values that cannot be mapped onto [`DamlValue`]: a `Value` whose
+/// `sum` is unset (a kind this client predates) or a scalar outside what the SDK models
+/// (e.g. a Numeric beyond `rust_decimal` precision).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownValuePolicy {
+    /// Fail the whole decode.
+    #[default]
+    Error,
+    /// Drop the nearest enclosing record field, list element or map entry.
+    SkipField,
+    /// Keep the value as [`DamlValue::Raw`] holding its encoded proto bytes.
+    PreserveRaw,
+}
+
+/// Decodes proto values into canton-core types under an [`UnknownValuePolicy`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValueDecoder {
+    policy: UnknownValuePolicy,
+}
+
+impl ValueDecoder {
+    pub fn new(policy: UnknownValuePolicy) -> Self {
+        Self { policy }
+    }
+
+    pub fn policy(&self) -> UnknownValuePolicy {
+        self.policy
+    }
+
+    /// Decode a single value. A top-level unknown value is an error even under
+    /// `SkipField`, since there is no enclosing field to drop.
+    pub fn decode_value(&self, value: proto::Value) -> SdkResult<DamlValue> {
+        self.decode(value)?
+            .ok_or_else(|| SdkError::Serialization("unsupported Ledger API value at top level".into()))
+    }
+
+    /// Decode a created event. Ledger API v2 events have no event id; it is derived as
+    /// `<offset>:<node_id>`, which is unique per event.
+    pub fn decode_created_event(&self, event: proto::CreatedEvent) -> SdkResult<CreatedEvent> {
+        let template_id = event
+            .template_id
+            .map(identifier_from_proto)
+            .ok_or_else(|| SdkError::Serialization("created event without template_id".into()))?;
+        let contract_key = match event.contract_key {
+            Some(key) => self.decode(key)?,
+            None => None,
+        };
+        Ok(CreatedEvent {
+            event_id: format!("{}:{}", event.offset, event.node_id),
+            contract_id: ContractId::new(event.contract_id),
+            template_id,
+            create_arguments: self.decode_record(event.create_arguments.unwrap_or_default())?,
+            contract_key,
+            signatories: event.signatories,
+            observers: event.observers,
+            agreement_text: String::new(),
+        })
+    }
+
+    /// Decode a record, applying the policy to each field.
+    pub fn decode_record(&self, record: proto::Record) -> SdkResult<DamlRecord> {
+        let mut fields = Vec::with_capacity(record.fields.len());
+        for field in record.fields {
+            if let Some(value) = self.decode(field.value.unwrap_or_default())? {
+                fields.push(RecordField {
+                    label: field.label,
+                    value,
+                });
+            }
+        }
+        Ok(DamlRecord {
+            record_id: record.record_id.map(identifier_from_proto),
+            fields,
+        })
+    }
+
+    /// `Ok(None)` means the value was skipped and the caller should drop its enclosing slot.
+    fn decode(&self, value: proto::Value) -> SdkResult<Option<DamlValue>> {
+        let sum = match value.sum {
+            Some(sum) => sum,
+            None => return self.unknown(proto::Value { sum: None }, "value kind not set"),
+        };
+        let decoded = match sum {
+            Sum::Unit(()) => DamlValue::Unit,
+            Sum::Bool(b) => DamlValue::Bool(b),
+            Sum::Int64(i) => DamlValue::Int64(i),
+            Sum::Text(t) => DamlValue::Text(t),
+            Sum::ContractId(c) => DamlValue::ContractId(ContractId::new(c)),
+            Sum::Numeric(n) => match Decimal::from_str(&n) {
+                Ok(d) => DamlValue::Numeric(d),
+                Err(e) => return self.unknown_sum(Sum::Numeric(n), format!("numeric: {}", e)),
+            },
+            Sum::Party(p) => match PartyId::new(p.clone()) {
+                Ok(party) => DamlValue::Party(party),
+                Err(e) => return self.unknown_sum(Sum::Party(p), format!("party: {}", e)),
+            },
+            Sum::Date(days) => match DateTime::from_timestamp(i64::from(days) * 86_400, 0) {
+                Some(dt) => DamlValue::Date(dt.date_naive()),
+                None => return self.unknown_sum(Sum::Date(days), "date out of range"),
+            },
+            Sum::Timestamp(micros) => match DateTime::<Utc>::from_timestamp_micros(micros) {
+                Some(ts) => DamlValue::Timestamp(ts),
+                None => return self.unknown_sum(Sum::Timestamp(micros), "timestamp out of range"),
+            },
+            Sum::Optional(opt) => match opt.value {
+                None => DamlValue::Optional(None),
+                Some(inner) => match self.decode(*inner)? {
+                    Some(v) => DamlValue::Optional(Some(Box::new(v))),
+                    None => return Ok(None),
+                },
+            },
+            Sum::List(list) => {
+                let mut values = Vec::with_capacity(list.elements.len());
+                for element in list.elements {
+                    if let Some(v) = self.decode(element)? {
+                        values.push(v);
+                    }
+                }
+                DamlValue::List(values)
+            }
+            Sum::TextMap(map) => {
+                let mut entries = HashMap::with_capacity(map.entries.len());
+                for entry in map.entries {
+                    if let Some(v) = self.decode(entry.value.unwrap_or_default())? {
+                        entries.insert(entry.key, v);
+                    }
+                }
+                DamlValue::TextMap(entries)
+            }
+            Sum::GenMap(map) => {
+                let mut entries = Vec::with_capacity(map.entries.len());
+                for entry in map.entries {
+                    let key = self.decode(entry.key.unwrap_or_default())?;
+                    let value = self.decode(entry.value.unwrap_or_default())?;
+                    if let (Some(k), Some(v)) = (key, value) {
+                        entries.push((k, v));
+                    }
+                }
+                DamlValue::GenMap(entries)
+            }
+            Sum::Record(record) => DamlValue::Record(self.decode_record(record)?),
+            Sum::Variant(variant) => {
+                let inner = match variant.value {
+                    Some(v) => self.decode(*v)?,
+                    None => self.decode(proto::Value::default())?,
+                };
+                match inner {
+                    Some(v) => DamlValue::Variant(DamlVariant {
+                        variant_id: variant.variant_id.map(identifier_from_proto),
+                        constructor: variant.constructor,
+                        value: Box::new(v),
+                    }),
+                    None => return Ok(None),
+                }
+            }
+            Sum::Enum(e) => DamlValue::Enum(DamlEnum {
+                enum_id: e.enum_id.map(identifier_from_proto),
+                constructor: e.constructor,
+            }),
+        };
+        Ok(Some(decoded))
+    }
+
+    fn unknown_sum(&self, sum: Sum, reason: impl Into<String>) -> SdkResult<Option<DamlValue>> {
+        self.unknown(proto::Value { sum: Some(sum) }, reason)
+    }
+
+    fn unknown(&self, raw: proto::Value, reason: impl Into<String>) -> SdkResult<Option<DamlValue>> {
+        match self.policy {
+            UnknownValuePolicy::Error => Err(SdkError::Serialization(format!(
+                "unsupported Ledger API value: {}",
+                reason.into()
+            ))),
+            UnknownValuePolicy::SkipField => Ok(None),
+            UnknownValuePolicy::PreserveRaw => Ok(Some(DamlValue::Raw(raw.encode_to_vec().into()))),
+        }
+    }
+}
+
+fn identifier_from_proto(id: proto::Identifier) -> Identifier {
+    Identifier::new(id.package_id, id.module_name, id.entity_name)
+}
+
+impl FromProto<proto::Identifier> for Identifier {
+    fn from_proto(proto: proto::Identifier) -> SdkResult<Self> {
+        Ok(identifier_from_proto(proto))
+    }
+}
+
+impl ToProto<proto::Identifier> for Identifier {
+    fn to_proto(&self) -> SdkResult<proto::Identifier> {
+        Ok(proto::Identifier {
+            package_id: self.package_id.clone(),
+            module_name: self.module_name.clone(),
+            entity_name: self.entity_name.clone(),
+        })
+    }
+}
+
+impl ToProto<proto::Value> for DamlValue {
+    fn to_proto(&self) -> SdkResult<proto::Value> {
+        let sum = match self {
+            DamlValue::Unit => Sum::Unit(()),
+            DamlValue::Bool(b) => Sum::Bool(*b),
+            DamlValue::Int64(i) => Sum::Int64(*i),
+            DamlValue::Numeric(d) => Sum::Numeric(d.to_string()),
+            DamlValue::Text(t) => Sum::Text(t.clone()),
+            DamlValue::Timestamp(ts) => Sum::Timestamp(ts.timestamp_micros()),
+            DamlValue::Date(date) => {
+                let days = date.signed_duration_since(DateTime::UNIX_EPOCH.date_naive()).num_days();
+                Sum::Date(i32::try_from(days).map_err(|_| {
+                    SdkError::Serialization(format!("date out of range: {}", date))
+                })?)
+            }
+            DamlValue::Party(p) => Sum::Party(p.to_string()),
+            DamlValue::ContractId(c) => Sum::ContractId(c.to_string()),
+            DamlValue::List(values) => Sum::List(proto::List {
+                elements: values.iter().map(ToProto::to_proto).collect::<SdkResult<_>>()?,
+            }),
+            DamlValue::Optional(value) => Sum::Optional(Box::new(proto::Optional {
+                value: value.as_ref().map(|v| v.to_proto().map(Box::new)).transpose()?,
+            })),
+            DamlValue::TextMap(map) => Sum::TextMap(proto::TextMap {
+                entries: map
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok(proto::text_map::Entry {
+                            key: key.clone(),
+                            value: Some(value.to_proto()?),
+                        })
+                    })
+                    .collect::<SdkResult<_>>()?,
+            }),
+            DamlValue::GenMap(entries) => Sum::GenMap(proto::GenMap {
+                entries: entries
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok(proto::gen_map::Entry {
+                            key: Some(key.to_proto()?),
+                            value: Some(value.to_proto()?),
+                        })
+                    })
+                    .collect::<SdkResult<_>>()?,
+            }),
+            DamlValue::Record(record) => Sum::Record(record.to_proto()?),
+            DamlValue::Variant(variant) => Sum::Variant(Box::new(proto::Variant {
+                variant_id: variant.variant_id.as_ref().map(ToProto::to_proto).transpose()?,
+                constructor: variant.constructor.clone(),
+                value: Some(Box::new(variant.value.to_proto()?)),
+            })),
+            DamlValue::Enum(e) => Sum::Enum(proto::Enum {
+                enum_id: e.enum_id.as_ref().map(ToProto::to_proto).transpose()?,
+                constructor: e.constructor.clone(),
+            }),
+            // Preserved values are re-sent exactly as they were received.
+            DamlValue::Raw(bytes) => {
+                return proto::Value::decode(bytes.clone()).map_err(|e| SdkError::Serialization(e.to_string()));
+            }
+        };
+        Ok(proto::Value { sum: Some(sum) })
+    }
+}
+
+impl ToProto<proto::Record> for DamlRecord {
+    fn to_proto(&self) -> SdkResult<proto::Record> {
+        Ok(proto::Record {
+            record_id: self.record_id.as_ref().map(ToProto::to_proto).transpose()?,
+            fields: self
+                .fields
+                .iter()
+                .map(|field| {
+                    Ok(proto::RecordField {
+                        label: field.label.clone(),
+                        value: Some(field.value.to_proto()?),
+                    })
+                })
+                .collect::<SdkResult<_>>()?,
+        })
+    }
+}
+
+impl ToProto<proto::Command> for Command {
+    fn to_proto(&self) -> SdkResult<proto::Command> {
+        use proto::command::Command as Kind;
+
+        let command = match self {
+            Command::Create(c) => Kind::Create(proto::CreateCommand {
+                template_id: Some(c.template_id.to_proto()?),
+                create_arguments: Some(c.create_arguments.to_proto()?),
+            }),
+            Command::Exercise(c) => Kind::Exercise(proto::ExerciseCommand {
+                template_id: Some(c.template_id.to_proto()?),
+                contract_id: c.contract_id.clone(),
+                choice: c.choice.clone(),
+                choice_argument: Some(c.choice_argument.to_proto()?),
+            }),
+            Command::ExerciseByKey(c) => Kind::ExerciseByKey(proto::ExerciseByKeyCommand {
+                template_id: Some(c.template_id.to_proto()?),
+                contract_key: Some(c.contract_key.to_proto()?),
+                choice: c.choice.clone(),
+                choice_argument: Some(c.choice_argument.to_proto()?),
+            }),
+            Command::CreateAndExercise(c) => Kind::CreateAndExercise(proto::CreateAndExerciseCommand {
+                template_id: Some(c.template_id.to_proto()?),
+                create_arguments: Some(c.create_arguments.to_proto()?),
+                choice: c.choice.clone(),
+                choice_argument: Some(c.choice_argument.to_proto()?),
+            }),
+        };
+        Ok(proto::Command { command: Some(command) })
+    }
+}
+
+impl ToProto<proto::Commands> for Commands {
+    /// `application_id` becomes the v2 `user_id`; `ledger_id` has no v2 counterpart.
+    fn to_proto(&self) -> SdkResult<proto::Commands> {
+        use proto::commands::DeduplicationPeriod;
+
+        let duration = |d: &std::time::Duration| prost_types::Duration {
+            seconds: d.as_secs() as i64,
+            nanos: d.subsec_nanos() as i32,
+        };
+        Ok(proto::Commands {
+            workflow_id: self.workflow_id.clone(),
+            user_id: self.application_id.clone(),
+            command_id: self.command_id.clone(),
+            commands: self.commands.iter().map(ToProto::to_proto).collect::<SdkResult<_>>()?,
+            deduplication_period: self
+                .deduplication_period
+                .as_ref()
+                .map(|d| DeduplicationPeriod::DeduplicationDuration(duration(d))),
+            min_ledger_time_abs: self.min_ledger_time_abs.map(|t| prost_types::Timestamp {
+                seconds: t.timestamp(),
+                nanos: t.timestamp_subsec_nanos() as i32,
+            }),
+            min_ledger_time_rel: self.min_ledger_time_rel.as_ref().map(duration),
+            act_as: self.act_as.clone(),
+            read_as: self.read_as.clone(),
+            submission_id: self.submission_id.clone().unwrap_or_default(),
+            disclosed_contracts: self
+                .disclosed_contracts
+                .iter()
+                .map(ToProto::to_proto)
+                .collect::<SdkResult<_>>()?,
+            ..Default::default()
+        })
+    }
+}
+
+impl ToProto<proto::DisclosedContract> for DisclosedContract {
+    /// The synchronizer is left unset; the participant infers it from the blob.
+    fn to_proto(&self) -> SdkResult<proto::DisclosedContract> {
+        Ok(proto::DisclosedContract {
+            template_id: Some(self.template_id.to_proto()?),
+            contract_id: self.contract_id.clone(),
+            created_event_blob: self.created_event_blob.clone(),
+            synchronizer_id: String::new(),
+        })
+    }
+}
+
+impl ToProto<proto::Filters> for Filters {
+    /// No inclusive filters means a wildcard over all templates.
+    fn to_proto(&self) -> SdkResult<proto::Filters> {
+        use proto::cumulative_filter::IdentifierFilter;
+
+        let Some(inclusive) = &self.inclusive else {
+            return Ok(proto::Filters {
+                cumulative: vec![proto::CumulativeFilter {
+                    identifier_filter: Some(IdentifierFilter::WildcardFilter(proto::WildcardFilter {
+                        include_created_event_blob: false,
+                    })),
+                }],
+            });
+        };
+        let mut cumulative = Vec::new();
+        for template_id in &inclusive.template_ids {
+            cumulative.push(proto::CumulativeFilter {
+                identifier_filter: Some(IdentifierFilter::TemplateFilter(proto::TemplateFilter {
+                    template_id: Some(template_id.to_proto()?),
+                    include_created_event_blob: false,
+                })),
+            });
+        }
+        for filter in &inclusive.interface_filters {
+            cumulative.push(proto::CumulativeFilter {
+                identifier_filter: Some(IdentifierFilter::InterfaceFilter(proto::InterfaceFilter {
+                    interface_id: Some(filter.interface_id.to_proto()?),
+                    include_interface_view: true,
+                    include_created_event_blob: filter.include_created_event_blob,
+                })),
+            });
+        }
+        Ok(proto::Filters { cumulative })
+    }
+}
+
+impl ToProto<proto::EventFormat> for TransactionFilter {
+    fn to_proto(&self) -> SdkResult<proto::EventFormat> {
+        let filters_by_party = self
+            .filters_by_party
+            .iter()
+            .map(|(party, filters)| Ok((party.clone(), filters.to_proto()?)))
+            .collect::<SdkResult<_>>()?;
+        Ok(proto::EventFormat {
+            filters_by_party,
+            filters_for_any_party: None,
+            verbose: true,
+        })
+    }
+}
+
+impl FromProto<proto::Value> for DamlValue {
+    fn from_proto(proto: proto::Value) -> SdkResult<Self> {
+        ValueDecoder::default().decode_value(proto)
+    }
+}
+
+impl FromProto<proto::Record> for DamlRecord {
+    fn from_proto(proto: proto::Record) -> SdkResult<Self> {
+        ValueDecoder::default().decode_record(proto)
+    }
+}
+
+impl FromProto<proto::CreatedEvent> for CreatedEvent {
+    fn from_proto(proto: proto::CreatedEvent) -> SdkResult<Self> {
+        ValueDecoder::default().decode_created_event(proto)
+    }
+}
+
+impl FromProto<proto::Completion> for Completion {
+    fn from_proto(proto: proto::Completion) -> SdkResult<Self> {
+        let status = match proto.status {
+            Some(status) => CompletionStatus::from_grpc(status.code, &status.message),
+            None => CompletionStatus::Succeeded,
+        };
+        Ok(Completion {
+            command_id: proto.command_id,
+            status,
+            update_id: non_empty(proto.update_id),
+            submission_id: non_empty(proto.submission_id),
+            act_as: proto.act_as.into_iter().map(PartyId::new_unchecked).collect(),
+            offset: proto.offset.to_string(),
+        })
+    }
+}
+
+impl FromProto<proto::admin::PartyDetails> for PartyDetails {
+    fn from_proto(proto: proto::admin::PartyDetails) -> SdkResult<Self> {
+        let annotations = proto.local_metadata.map(|m| m.annotations).unwrap_or_default();
+        Ok(PartyDetails {
+            party: PartyId::new_unchecked(proto.party),
+            is_local: proto.is_local,
+            display_name: annotations.get(PartyDetails::DISPLAY_NAME_ANNOTATION).cloned(),
+            annotations,
+            identity_provider_id: non_empty(proto.identity_provider_id),
+        })
+    }
+}
+
+fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() { None } else { Some(s) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_with_unknown_field() -> proto::Record {
+        proto::Record {
+            record_id: None,
+            fields: vec![
+                proto::RecordField {
+                    label: "owner".into(),
+                    value: Some(proto::Value {
+                        sum: Some(Sum::Party("alice::1220ab".into())),
+                    }),
+                },
+                proto::RecordField {
+                    label: "future".into(),
+                    value: Some(proto::Value { sum: None }),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn unknown_value_fails_under_error_policy() {
+        let res = ValueDecoder::new(UnknownValuePolicy::Error).decode_record(record_with_unknown_field());
+        assert!(matches!(res, Err(SdkError::Serialization(_))));
+    }
+
+    #[test]
+    fn unknown_value_is_dropped_under_skip_policy() {
+        let record = ValueDecoder::new(UnknownValuePolicy::SkipField)
+            .decode_record(record_with_unknown_field())
+            .unwrap();
+        assert_eq!(record.fields.len(), 1);
+        assert!(record.get("owner").is_some());
+        assert!(record.get("future").is_none());
+    }
+
+    #[test]
+    fn unknown_value_is_kept_under_preserve_raw_policy() {
+        let record = ValueDecoder::new(UnknownValuePolicy::PreserveRaw)
+            .decode_record(record_with_unknown_field())
+            .unwrap();
+        assert_eq!(record.fields.len(), 2);
+        assert!(matches!(record.get("future"), Some(DamlValue::Raw(_))));
+    }
+
+    #[test]
+    fn interface_filter_maps_to_proto_interface_filter() {
+        use proto::cumulative_filter::IdentifierFilter;
+
+        let party = PartyId::new("alice::1220ab").unwrap();
+        let interface_id = Identifier::new("pkg", "Token.Holding", "Holding");
+        let event_format = TransactionFilter::for_interface(&party, interface_id).to_proto().unwrap();
+
+        let filters = &event_format.filters_by_party["alice::1220ab"];
+        assert_eq!(filters.cumulative.len(), 1);
+        match &filters.cumulative[0].identifier_filter {
+            Some(IdentifierFilter::InterfaceFilter(f)) => {
+                let id = f.interface_id.as_ref().unwrap();
+                assert_eq!((id.module_name.as_str(), id.entity_name.as_str()), ("Token.Holding", "Holding"));
+                assert!(f.include_interface_view);
+            }
+            other => panic!("expected interface filter, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn disclosed_contracts_are_carried_into_the_submit_request() {
+        let template_id = Identifier::new("pkg", "Token.Holding", "Holding");
+        let mut commands = Commands {
+            ledger_id: None,
+            workflow_id: String::new(),
+            application_id: "app".into(),
+            command_id: "cmd-1".into(),
+            act_as: vec!["alice::1220ab".into()],
+            read_as: Vec::new(),
+            commands: vec![Command::Exercise(canton_core::types::ExerciseCommand {
+                template_id: template_id.clone(),
+                contract_id: "00aa".into(),
+                choice: "Transfer".into(),
+                choice_argument: DamlRecord::new().field("newOwner", DamlValue::text("bob")).into(),
+            })],
+            min_ledger_time_abs: None,
+            min_ledger_time_rel: None,
+            deduplication_period: None,
+            submission_id: None,
+            disclosed_contracts: Vec::new(),
+        };
+        commands.with_disclosed_contract(DisclosedContract {
+            contract_id: "00aa".into(),
+            template_id,
+            created_event_blob: vec![1, 2, 3],
+        });
+
+        let request = commands.to_proto().unwrap();
+        assert_eq!(request.user_id, "app");
+        assert_eq!(request.commands.len(), 1);
+        assert_eq!(request.disclosed_contracts.len(), 1);
+        let disclosed = &request.disclosed_contracts[0];
+        assert_eq!(disclosed.contract_id, "00aa");
+        assert_eq!(disclosed.created_event_blob, vec![1, 2, 3]);
+        assert_eq!(disclosed.template_id.as_ref().unwrap().entity_name, "Holding");
+    }
+
+    #[test]
+    fn values_round_trip_through_proto() {
+        let value = DamlValue::Record(
+            DamlRecord::new()
+                .field("owner", DamlValue::Party(PartyId::new("alice::1220ab").unwrap()))
+                .field("amount", DamlValue::Numeric(Decimal::new(1050, 2)))
+                .field("due", DamlValue::Date(chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()))
+                .field("tags", DamlValue::List(vec![DamlValue::text("a"), DamlValue::Optional(None)])),
+        );
+        let decoded = DamlValue::from_proto(value.to_proto().unwrap()).unwrap();
+        assert_eq!(decoded, value);
+    }
+}
@@ -10,9 +10,15 @@ pub mod generated {
 #[cfg(proto_compiled)]
 pub mod client;
 
+#[cfg(proto_compiled)]
+pub mod conversion;
+
 #[cfg(proto_compiled)]
 pub use client::LedgerClient;
 
+#[cfg(proto_compiled)]
+pub use conversion::{UnknownValuePolicy, ValueDecoder};
+
 /// Stub client when proto is not compiled (no proto files in proto/).
 #[cfg(not(proto_compiled))]
 #[derive(Debug)]
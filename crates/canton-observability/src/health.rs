@@ -0,0 +1,361 @@
+//! Health checks: individual probes and a registry that aggregates them.
+//! See research/07-production-ready-patterns.md §4.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use canton_core::SdkResult;
+use tokio::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthCheckResult {
+    pub status: HealthStatus,
+    pub message: Option<String>,
+    pub details: HashMap<String, String>,
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl HealthCheckResult {
+    pub fn new(status: HealthStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: Some(message.into()),
+            details: HashMap::new(),
+            checked_at: chrono::Utc::now(),
+        }
+    }
+
+    pub fn with_detail(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.details.insert(key.into(), value.into());
+        self
+    }
+}
+
+#[async_trait]
+pub trait HealthCheck: Send + Sync {
+    fn name(&self) -> &str;
+    async fn check(&self) -> HealthCheckResult;
+}
+
+/// Which probe verdicts a check feeds into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Probe {
+    /// Process is alive; failing means it should be restarted.
+    Liveness,
+    /// Process can serve traffic (e.g. the ledger is reachable).
+    Readiness,
+    Both,
+}
+
+impl Probe {
+    fn includes(self, probe: Probe) -> bool {
+        self == Probe::Both || self == probe
+    }
+}
+
+/// How a registered check contributes to [`HealthChecker::liveness`] and
+/// [`HealthChecker::readiness`].
+#[derive(Debug, Clone, Copy)]
+pub struct CheckOptions {
+    pub probe: Probe,
+    /// A failing critical check makes the overall verdict Unhealthy; a failing non-critical
+    /// one only Degraded.
+    pub critical: bool,
+    /// A check that does not finish in time counts as Unhealthy.
+    pub timeout: Duration,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        Self {
+            probe: Probe::Readiness,
+            critical: true,
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+struct RegisteredCheck {
+    check: Arc<dyn HealthCheck>,
+    options: CheckOptions,
+}
+
+/// Registry of health checks.
+#[derive(Default)]
+pub struct HealthChecker {
+    checks: RwLock<HashMap<String, RegisteredCheck>>,
+}
+
+impl HealthChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a critical readiness check with the default timeout; replaces an existing check
+    /// with the same name.
+    pub async fn register(&self, check: Arc<dyn HealthCheck>) {
+        self.register_with(check, CheckOptions::default()).await;
+    }
+
+    pub async fn register_with(&self, check: Arc<dyn HealthCheck>, options: CheckOptions) {
+        let name = check.name().to_string();
+        self.checks.write().await.insert(name, RegisteredCheck { check, options });
+    }
+
+    /// Run all checks. Overall status is the worst individual status.
+    pub async fn check_all(&self) -> OverallHealth {
+        let checks = self.checks.read().await;
+        let mut results = HashMap::new();
+        let mut overall_status = HealthStatus::Healthy;
+        for (name, registered) in checks.iter() {
+            let result = registered.check.check().await;
+            match result.status {
+                HealthStatus::Unhealthy => overall_status = HealthStatus::Unhealthy,
+                HealthStatus::Degraded if overall_status == HealthStatus::Healthy => {
+                    overall_status = HealthStatus::Degraded;
+                }
+                _ => {}
+            }
+            results.insert(name.clone(), result);
+        }
+        OverallHealth {
+            status: overall_status,
+            checks: results,
+            checked_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Run the readiness checks concurrently, each bounded by its timeout. Unhealthy if a critical
+    /// check fails, Degraded if any other check is not healthy, Healthy otherwise.
+    pub async fn readiness(&self) -> OverallHealth {
+        self.probe(Probe::Readiness).await
+    }
+
+    /// Like [`readiness`](Self::readiness), over the liveness checks only. Healthy when none
+    /// are registered.
+    pub async fn liveness(&self) -> OverallHealth {
+        self.probe(Probe::Liveness).await
+    }
+
+    async fn probe(&self, probe: Probe) -> OverallHealth {
+        let checks = self.checks.read().await;
+        let selected = checks.iter().filter(|(_, registered)| registered.options.probe.includes(probe));
+        let runs = selected.map(|(name, registered)| async move {
+            let timeout = registered.options.timeout;
+            let result = tokio::time::timeout(timeout, registered.check.check())
+                .await
+                .unwrap_or_else(|_| {
+                    HealthCheckResult::new(HealthStatus::Unhealthy, format!("check timed out after {:?}", timeout))
+                });
+            (name.clone(), registered.options.critical, result)
+        });
+        let mut overall_status = HealthStatus::Healthy;
+        let mut results = HashMap::new();
+        for (name, critical, result) in futures::future::join_all(runs).await {
+            overall_status = match (result.status, critical) {
+                (HealthStatus::Healthy, _) => overall_status,
+                (HealthStatus::Unhealthy, true) => HealthStatus::Unhealthy,
+                _ if overall_status == HealthStatus::Unhealthy => overall_status,
+                _ => HealthStatus::Degraded,
+            };
+            results.insert(name, result);
+        }
+        OverallHealth {
+            status: overall_status,
+            checks: results,
+            checked_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Run a single check by name.
+    pub async fn check(&self, name: &str) -> Option<HealthCheckResult> {
+        let check = self.checks.read().await.get(name)?.check.clone();
+        Some(check.check().await)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OverallHealth {
+    pub status: HealthStatus,
+    pub checks: HashMap<String, HealthCheckResult>,
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+}
+
+// -----------------------------------------------------------------------------
+// Ledger connectivity
+// -----------------------------------------------------------------------------
+
+/// Minimal view of a ledger connection, implemented by `canton_ledger_api::LedgerClient`.
+#[async_trait]
+pub trait LedgerProbe: Send + Sync {
+    /// Cheap local connectivity flag (no network round-trip). Probes that cannot observe
+    /// connectivity locally keep the default, and readiness then rests on
+    /// [`fetch_ledger_id`](Self::fetch_ledger_id) alone.
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    /// Lightweight round-trip to the participant returning the ledger id.
+    async fn fetch_ledger_id(&self) -> SdkResult<String>;
+}
+
+/// Readiness probe: healthy when the client reports connected and a ledger-id fetch
+/// completes within `timeout`.
+pub struct LedgerHealthCheck {
+    probe: Arc<dyn LedgerProbe>,
+    timeout: Duration,
+}
+
+impl LedgerHealthCheck {
+    pub fn new(probe: Arc<dyn LedgerProbe>, timeout: Duration) -> Self {
+        Self { probe, timeout }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for LedgerHealthCheck {
+    fn name(&self) -> &str {
+        "ledger"
+    }
+
+    async fn check(&self) -> HealthCheckResult {
+        if !self.probe.is_connected() {
+            return HealthCheckResult::new(HealthStatus::Unhealthy, "ledger client not connected");
+        }
+        let start = Instant::now();
+        match tokio::time::timeout(self.timeout, self.probe.fetch_ledger_id()).await {
+            Ok(Ok(ledger_id)) => HealthCheckResult::new(HealthStatus::Healthy, "connected")
+                .with_detail("ledger_id", ledger_id)
+                .with_detail("latency_ms", start.elapsed().as_millis().to_string()),
+            Ok(Err(e)) => HealthCheckResult::new(HealthStatus::Unhealthy, e.to_string()),
+            Err(_) => HealthCheckResult::new(
+                HealthStatus::Unhealthy,
+                format!("ledger id fetch timed out after {:?}", self.timeout),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    struct StubLedger {
+        connected: AtomicBool,
+    }
+
+    #[async_trait]
+    impl LedgerProbe for StubLedger {
+        fn is_connected(&self) -> bool {
+            self.connected.load(Ordering::SeqCst)
+        }
+
+        async fn fetch_ledger_id(&self) -> SdkResult<String> {
+            Ok("sandbox".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn ledger_health_follows_connectivity() {
+        let stub = Arc::new(StubLedger {
+            connected: AtomicBool::new(true),
+        });
+        let checker = HealthChecker::new();
+        checker
+            .register(Arc::new(LedgerHealthCheck::new(stub.clone(), Duration::from_secs(1))))
+            .await;
+
+        let health = checker.check_all().await;
+        assert_eq!(health.status, HealthStatus::Healthy);
+        assert_eq!(health.checks["ledger"].details["ledger_id"], "sandbox");
+
+        stub.connected.store(false, Ordering::SeqCst);
+        let health = checker.check_all().await;
+        assert_eq!(health.status, HealthStatus::Unhealthy);
+    }
+
+    struct Fixed {
+        name: &'static str,
+        status: HealthStatus,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl HealthCheck for Fixed {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn check(&self) -> HealthCheckResult {
+            tokio::time::sleep(self.delay).await;
+            HealthCheckResult::new(self.status, self.name)
+        }
+    }
+
+    fn fixed(name: &'static str, status: HealthStatus) -> Arc<Fixed> {
+        Arc::new(Fixed { name, status, delay: Duration::ZERO })
+    }
+
+    #[tokio::test]
+    async fn readiness_distinguishes_critical_failures() {
+        let non_critical = CheckOptions {
+            critical: false,
+            ..CheckOptions::default()
+        };
+        let checker = HealthChecker::new();
+        checker.register(fixed("ledger", HealthStatus::Healthy)).await;
+        checker.register_with(fixed("metrics", HealthStatus::Unhealthy), non_critical).await;
+        let report = checker.readiness().await;
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert_eq!(report.checks["metrics"].status, HealthStatus::Unhealthy);
+
+        let slow = Arc::new(Fixed {
+            name: "keystore",
+            status: HealthStatus::Healthy,
+            delay: Duration::from_secs(5),
+        });
+        let critical = CheckOptions {
+            timeout: Duration::from_millis(20),
+            ..CheckOptions::default()
+        };
+        checker.register_with(slow, critical).await;
+        let report = checker.readiness().await;
+        assert_eq!(report.status, HealthStatus::Unhealthy);
+        assert!(report.checks["keystore"].message.as_deref().unwrap().contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn ledger_outage_fails_readiness_but_not_liveness() {
+        let stub = Arc::new(StubLedger {
+            connected: AtomicBool::new(false),
+        });
+        let checker = HealthChecker::new();
+        checker
+            .register(Arc::new(LedgerHealthCheck::new(stub, Duration::from_secs(1))))
+            .await;
+        let event_loop = CheckOptions {
+            probe: Probe::Both,
+            ..CheckOptions::default()
+        };
+        checker.register_with(fixed("event_loop", HealthStatus::Healthy), event_loop).await;
+
+        let readiness = checker.readiness().await;
+        assert_eq!(readiness.status, HealthStatus::Unhealthy);
+        assert_eq!(readiness.checks.len(), 2);
+
+        let liveness = checker.liveness().await;
+        assert_eq!(liveness.status, HealthStatus::Healthy);
+        assert!(!liveness.checks.contains_key("ledger"));
+        assert!(liveness.checks.contains_key("event_loop"));
+    }
+}
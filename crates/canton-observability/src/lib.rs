@@ -1,4 +1,13 @@
-//! Canton Observability — logging, metrics, tracing (stub).
+//! Canton Observability — logging, metrics, tracing, health checks.
 
-// TODO: init_logging, SdkMetrics, HealthCheck
+// TODO: init_logging
 // See research/07, 08
+
+pub mod health;
+pub mod metrics;
+
+pub use health::{
+    CheckOptions, HealthCheck, HealthCheckResult, HealthChecker, HealthStatus, LedgerHealthCheck, LedgerProbe,
+    OverallHealth, Probe,
+};
+pub use metrics::{MetricKind, MetricsRecorder};
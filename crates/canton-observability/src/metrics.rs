@@ -0,0 +1,336 @@
+//! In-process metrics registry (counters, gauges, histograms) with Prometheus text export.
+//! See research/07-production-ready-patterns.md §3.2.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use canton_core::config::ObservabilityConfig;
+
+/// Label value of the series that absorbs label sets beyond the cardinality limit.
+pub const OVERFLOW_LABEL_VALUE: &str = "__overflow__";
+
+/// Default histogram buckets (seconds), matching the Prometheus client defaults.
+pub const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+/// Sorted label pairs identifying one series within a metric.
+type LabelSet = Vec<(String, String)>;
+
+#[derive(Debug, Clone)]
+struct HistogramState {
+    /// Per-bucket (non-cumulative) counts; rendered cumulatively.
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Debug, Clone)]
+enum SeriesValue {
+    Scalar(f64),
+    Histogram(HistogramState),
+}
+
+#[derive(Debug)]
+struct MetricFamily {
+    kind: MetricKind,
+    help: String,
+    buckets: Vec<f64>,
+    series: BTreeMap<LabelSet, SeriesValue>,
+    overflowed: bool,
+}
+
+impl MetricFamily {
+    fn new(kind: MetricKind, help: &str, buckets: Vec<f64>) -> Self {
+        Self {
+            kind,
+            help: help.to_string(),
+            buckets,
+            series: BTreeMap::new(),
+            overflowed: false,
+        }
+    }
+}
+
+/// Thread-safe metrics registry. Recording to an unregistered name registers it on the fly
+/// (without help text, default buckets for histograms); recording with the wrong kind is ignored.
+#[derive(Debug, Default)]
+pub struct MetricsRecorder {
+    families: Mutex<BTreeMap<String, MetricFamily>>,
+    cardinality_limit: Option<usize>,
+}
+
+impl MetricsRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_config(config: &ObservabilityConfig) -> Self {
+        match config.metrics_cardinality_limit {
+            Some(limit) => Self::with_cardinality_limit(limit),
+            None => Self::new(),
+        }
+    }
+
+    /// Keep at most `limit` distinct label sets per metric; samples for further label sets are
+    /// recorded under the same label names with value [`OVERFLOW_LABEL_VALUE`].
+    pub fn with_cardinality_limit(limit: usize) -> Self {
+        Self {
+            cardinality_limit: Some(limit),
+            ..Self::default()
+        }
+    }
+
+    pub fn register_counter(&self, name: &str, help: &str) {
+        self.register(name, MetricKind::Counter, help, Vec::new());
+    }
+
+    pub fn register_gauge(&self, name: &str, help: &str) {
+        self.register(name, MetricKind::Gauge, help, Vec::new());
+    }
+
+    pub fn register_histogram(&self, name: &str, help: &str, buckets: &[f64]) {
+        let mut buckets = buckets.to_vec();
+        buckets.sort_by(|a, b| a.total_cmp(b));
+        buckets.dedup();
+        self.register(name, MetricKind::Histogram, help, buckets);
+    }
+
+    fn register(&self, name: &str, kind: MetricKind, help: &str, buckets: Vec<f64>) {
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        families
+            .entry(name.to_string())
+            .or_insert_with(|| MetricFamily::new(kind, help, buckets));
+    }
+
+    pub fn increment_counter(&self, name: &str, labels: &[(&str, &str)], by: f64) {
+        self.with_series(name, MetricKind::Counter, labels, |value| {
+            if let SeriesValue::Scalar(v) = value {
+                *v += by;
+            }
+        });
+    }
+
+    pub fn set_gauge(&self, name: &str, labels: &[(&str, &str)], to: f64) {
+        self.with_series(name, MetricKind::Gauge, labels, |value| {
+            if let SeriesValue::Scalar(v) = value {
+                *v = to;
+            }
+        });
+    }
+
+    pub fn observe_histogram(&self, name: &str, labels: &[(&str, &str)], observation: f64) {
+        let buckets = {
+            let families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+            families.get(name).map(|f| f.buckets.clone())
+        }
+        .unwrap_or_else(|| DEFAULT_BUCKETS.to_vec());
+        self.with_series(name, MetricKind::Histogram, labels, |value| {
+            if let SeriesValue::Histogram(h) = value {
+                if let Some(i) = buckets.iter().position(|b| observation <= *b) {
+                    h.counts[i] += 1;
+                }
+                h.sum += observation;
+                h.count += 1;
+            }
+        });
+    }
+
+    fn with_series(
+        &self,
+        name: &str,
+        kind: MetricKind,
+        labels: &[(&str, &str)],
+        update: impl FnOnce(&mut SeriesValue),
+    ) {
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let family = families.entry(name.to_string()).or_insert_with(|| {
+            let buckets = if kind == MetricKind::Histogram {
+                DEFAULT_BUCKETS.to_vec()
+            } else {
+                Vec::new()
+            };
+            MetricFamily::new(kind, "", buckets)
+        });
+        if family.kind != kind {
+            tracing::warn!(metric = name, "metric recorded with mismatched kind; ignoring");
+            return;
+        }
+        let mut key: LabelSet = labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        key.sort();
+        if let Some(limit) = self.cardinality_limit {
+            if !key.is_empty() && !family.series.contains_key(&key) && family.series.len() >= limit {
+                if !family.overflowed {
+                    family.overflowed = true;
+                    tracing::warn!(metric = name, limit, "metric cardinality limit reached; folding new label sets into overflow series");
+                }
+                for (_, value) in key.iter_mut() {
+                    *value = OVERFLOW_LABEL_VALUE.to_string();
+                }
+            }
+        }
+        let bucket_count = family.buckets.len();
+        let value = family.series.entry(key).or_insert_with(|| match kind {
+            MetricKind::Histogram => SeriesValue::Histogram(HistogramState {
+                counts: vec![0; bucket_count],
+                sum: 0.0,
+                count: 0,
+            }),
+            _ => SeriesValue::Scalar(0.0),
+        });
+        update(value);
+    }
+
+    /// Render all metrics in the Prometheus text exposition format (version 0.0.4).
+    pub fn render_prometheus(&self) -> String {
+        let families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+        for (name, family) in families.iter() {
+            if !family.help.is_empty() {
+                let _ = writeln!(out, "# HELP {} {}", name, escape_help(&family.help));
+            }
+            let _ = writeln!(out, "# TYPE {} {}", name, family.kind.as_str());
+            for (labels, value) in &family.series {
+                match value {
+                    SeriesValue::Scalar(v) => {
+                        let _ = writeln!(out, "{}{} {}", name, render_labels(labels, None), format_value(*v));
+                    }
+                    SeriesValue::Histogram(h) => {
+                        let mut cumulative = 0;
+                        for (bound, count) in family.buckets.iter().zip(&h.counts) {
+                            cumulative += count;
+                            let le = format_value(*bound);
+                            let _ = writeln!(
+                                out,
+                                "{}_bucket{} {}",
+                                name,
+                                render_labels(labels, Some(&le)),
+                                cumulative
+                            );
+                        }
+                        let _ = writeln!(
+                            out,
+                            "{}_bucket{} {}",
+                            name,
+                            render_labels(labels, Some("+Inf")),
+                            h.count
+                        );
+                        let _ = writeln!(out, "{}_sum{} {}", name, render_labels(labels, None), format_value(h.sum));
+                        let _ = writeln!(out, "{}_count{} {}", name, render_labels(labels, None), h.count);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn render_labels(labels: &LabelSet, le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label_value(v)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{}\"", le));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn escape_label_value(v: &str) -> String {
+    v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn escape_help(v: &str) -> String {
+    v.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn format_value(v: f64) -> String {
+    if v.is_nan() {
+        "NaN".into()
+    } else if v.is_infinite() {
+        if v > 0.0 { "+Inf".into() } else { "-Inf".into() }
+    } else {
+        v.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_labeled_counter_and_histogram() {
+        let recorder = MetricsRecorder::new();
+        recorder.register_counter("canton_sdk_commands_submitted", "Total commands submitted");
+        recorder.register_histogram("canton_sdk_command_latency_seconds", "Command latency", &[0.1, 1.0]);
+
+        recorder.increment_counter("canton_sdk_commands_submitted", &[("template", "Iou \"v1\"")], 2.0);
+        recorder.observe_histogram("canton_sdk_command_latency_seconds", &[("op", "submit")], 0.05);
+        recorder.observe_histogram("canton_sdk_command_latency_seconds", &[("op", "submit")], 0.5);
+
+        let text = recorder.render_prometheus();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "# HELP canton_sdk_command_latency_seconds Command latency",
+                "# TYPE canton_sdk_command_latency_seconds histogram",
+                "canton_sdk_command_latency_seconds_bucket{op=\"submit\",le=\"0.1\"} 1",
+                "canton_sdk_command_latency_seconds_bucket{op=\"submit\",le=\"1\"} 2",
+                "canton_sdk_command_latency_seconds_bucket{op=\"submit\",le=\"+Inf\"} 2",
+                "canton_sdk_command_latency_seconds_sum{op=\"submit\"} 0.55",
+                "canton_sdk_command_latency_seconds_count{op=\"submit\"} 2",
+                "# HELP canton_sdk_commands_submitted Total commands submitted",
+                "# TYPE canton_sdk_commands_submitted counter",
+                "canton_sdk_commands_submitted{template=\"Iou \\\"v1\\\"\"} 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn cardinality_limit_folds_excess_series_into_overflow() {
+        let recorder = MetricsRecorder::with_cardinality_limit(3);
+        for party in 0..10 {
+            let party = format!("party-{}", party);
+            recorder.increment_counter("canton_sdk_submissions", &[("party", &party)], 1.0);
+        }
+        recorder.increment_counter("canton_sdk_submissions", &[("party", "party-0")], 1.0);
+
+        let text = recorder.render_prometheus();
+        let series: Vec<&str> = text.lines().filter(|l| !l.starts_with('#')).collect();
+        assert_eq!(
+            series,
+            vec![
+                "canton_sdk_submissions{party=\"__overflow__\"} 7",
+                "canton_sdk_submissions{party=\"party-0\"} 2",
+                "canton_sdk_submissions{party=\"party-1\"} 1",
+                "canton_sdk_submissions{party=\"party-2\"} 1",
+            ]
+        );
+    }
+}
@@ -1,4 +1,10 @@
-//! Canton Reliability — circuit breaker, rate limiter, retry (stub).
+//! Canton Reliability — circuit breaker, rate limiter, retry.
 
-// TODO: CircuitBreaker, RateLimiter, RetryPolicy
+pub mod rate_limiter;
+pub mod retry;
+
+pub use rate_limiter::{RateLimitStrategy, RateLimiter};
+pub use retry::RetryPolicy;
+
+// TODO: CircuitBreaker
 // See research/07, 08
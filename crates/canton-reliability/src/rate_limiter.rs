@@ -0,0 +1,362 @@
+//! Rate limiting keyed by caller (e.g. party id) and operation.
+//! See research/07-production-ready-patterns.md §2.3.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use canton_core::config::RateLimiterConfig;
+use canton_core::error::{SdkError, SdkResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitStrategy {
+    /// At most `max_requests` per window, reset at window boundaries.
+    FixedWindow,
+    /// Bursts up to `max_requests`, refilled continuously over the window.
+    TokenBucket,
+}
+
+impl RateLimitStrategy {
+    fn parse(s: &str) -> SdkResult<Self> {
+        match s {
+            "" | "token_bucket" => Ok(Self::TokenBucket),
+            "fixed_window" => Ok(Self::FixedWindow),
+            other => Err(SdkError::Config(format!("unknown rate limiter strategy: {:?}", other))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Limit {
+    max_requests: u64,
+    window: Duration,
+}
+
+impl Limit {
+    fn new(max_requests: u64, window_secs: u64, name: &str) -> SdkResult<Self> {
+        if max_requests == 0 || window_secs == 0 {
+            return Err(SdkError::Config(format!(
+                "rate limit for {} needs non-zero max_requests and window_secs",
+                name
+            )));
+        }
+        Ok(Self {
+            max_requests,
+            window: Duration::from_secs(window_secs),
+        })
+    }
+}
+
+/// Token-bucket level (tokens left, last refill) or fixed-window usage (requests, window start).
+#[derive(Debug)]
+struct Bucket {
+    level: f64,
+    at: Instant,
+}
+
+impl Bucket {
+    fn new(strategy: RateLimitStrategy, limit: Limit, now: Instant) -> Self {
+        let level = match strategy {
+            RateLimitStrategy::TokenBucket => limit.max_requests as f64,
+            RateLimitStrategy::FixedWindow => 0.0,
+        };
+        Self { level, at: now }
+    }
+
+    /// Refill (or roll the window) up to `now`, then report whether `weight` fits; if not,
+    /// how long until it would.
+    fn check(&mut self, strategy: RateLimitStrategy, limit: Limit, weight: f64, now: Instant) -> Result<(), Duration> {
+        let capacity = limit.max_requests as f64;
+        match strategy {
+            RateLimitStrategy::TokenBucket => {
+                let per_sec = capacity / limit.window.as_secs_f64();
+                let elapsed = now.saturating_duration_since(self.at).as_secs_f64();
+                self.level = (self.level + elapsed * per_sec).min(capacity);
+                self.at = now;
+                if self.level >= weight {
+                    Ok(())
+                } else {
+                    Err(Duration::from_secs_f64((weight - self.level) / per_sec))
+                }
+            }
+            RateLimitStrategy::FixedWindow => {
+                if now.saturating_duration_since(self.at) >= limit.window {
+                    self.level = 0.0;
+                    self.at = now;
+                }
+                if self.level + weight <= capacity {
+                    Ok(())
+                } else {
+                    Err((self.at + limit.window).saturating_duration_since(now))
+                }
+            }
+        }
+    }
+
+    /// Whether the bucket has fully refilled (or its window has ended) by `now`, i.e. it is
+    /// indistinguishable from a fresh one and can be dropped.
+    fn is_idle(&self, strategy: RateLimitStrategy, limit: Limit, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.at);
+        match strategy {
+            RateLimitStrategy::TokenBucket => {
+                let capacity = limit.max_requests as f64;
+                self.level + elapsed.as_secs_f64() * capacity / limit.window.as_secs_f64() >= capacity
+            }
+            RateLimitStrategy::FixedWindow => elapsed >= limit.window,
+        }
+    }
+
+    fn consume(&mut self, strategy: RateLimitStrategy, weight: f64) {
+        match strategy {
+            RateLimitStrategy::TokenBucket => self.level -= weight,
+            RateLimitStrategy::FixedWindow => self.level += weight,
+        }
+    }
+}
+
+/// Operation name used for the shared bucket of operations without their own limit.
+const DEFAULT_OPERATION: &str = "";
+
+/// Number of keyed buckets below which idle ones are not swept.
+const MIN_SWEEP_SIZE: usize = 1024;
+
+#[derive(Debug, Default)]
+struct State {
+    keyed: HashMap<(String, String), Bucket>,
+    global: Option<Bucket>,
+    /// Size at which inserting a new keyed bucket first drops the idle ones; doubles with the
+    /// live set so sweeping stays amortised O(1) per request.
+    sweep_at: usize,
+}
+
+pub struct RateLimiter {
+    strategy: RateLimitStrategy,
+    default_limit: Limit,
+    operation_limits: HashMap<String, Limit>,
+    global_limit: Option<Limit>,
+    state: Mutex<State>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimiterConfig) -> SdkResult<Self> {
+        let operation_limits = config
+            .operations
+            .iter()
+            .map(|(op, l)| Ok((op.clone(), Limit::new(l.max_requests, l.window_secs, op)?)))
+            .collect::<SdkResult<_>>()?;
+        let global_limit = config
+            .global_max_per_sec
+            .map(|max| Limit::new(max.into(), 1, "global ceiling"))
+            .transpose()?;
+        Ok(Self {
+            strategy: RateLimitStrategy::parse(&config.strategy)?,
+            default_limit: Limit::new(config.max_requests, config.window_secs, "default")?,
+            operation_limits,
+            global_limit,
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    pub fn strategy(&self) -> RateLimitStrategy {
+        self.strategy
+    }
+
+    /// Count one `operation` request for `key`. Operations without a configured limit share the
+    /// key's default bucket. Fails with [`SdkError::RateLimited`] when the key's bucket or the
+    /// global ceiling is exhausted; a rejected request consumes nothing.
+    pub fn check_limit(&self, key: &str, operation: &str) -> SdkResult<()> {
+        self.check_at(key, operation, 1, Instant::now())
+    }
+
+    /// Like [`check_limit`](Self::check_limit), but the request costs `weight` units of budget
+    /// (e.g. the number of commands in a batch). A weight above the bucket's capacity can never
+    /// be admitted and is a [`SdkError::Validation`] error.
+    pub fn check_limit_weighted(&self, key: &str, operation: &str, weight: u32) -> SdkResult<()> {
+        self.check_at(key, operation, weight, Instant::now())
+    }
+
+    fn limit_for(&self, operation: &str) -> (&str, Limit) {
+        match self.operation_limits.get_key_value(operation) {
+            Some((operation, limit)) => (operation, *limit),
+            None => (DEFAULT_OPERATION, self.default_limit),
+        }
+    }
+
+    fn check_at(&self, key: &str, operation: &str, weight: u32, now: Instant) -> SdkResult<()> {
+        let (operation, limit) = self.limit_for(operation);
+        let capacity = self.global_limit.map_or(limit.max_requests, |g| g.max_requests.min(limit.max_requests));
+        if u64::from(weight) > capacity {
+            return Err(SdkError::Validation {
+                field: "weight".into(),
+                message: format!("request weight {} exceeds rate limit capacity {}", weight, capacity),
+            });
+        }
+        let strategy = self.strategy;
+        let weight = f64::from(weight);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let State { keyed, global, sweep_at } = &mut *state;
+        let id = (key.to_string(), operation.to_string());
+        // A key's bucket is only stored once it admits a request, so rejected callers cost nothing.
+        let mut fresh = None;
+        let bucket = match keyed.get_mut(&id) {
+            Some(bucket) => bucket,
+            None => fresh.insert(Bucket::new(strategy, limit, now)),
+        };
+        let mut verdict = bucket.check(strategy, limit, weight, now);
+        let global = self.global_limit.map(|global_limit| {
+            let bucket = global.get_or_insert_with(|| Bucket::new(strategy, global_limit, now));
+            if let Err(wait) = bucket.check(strategy, global_limit, weight, now) {
+                verdict = Err(verdict.err().map_or(wait, |w| w.max(wait)));
+            }
+            bucket
+        });
+        verdict.map_err(|retry_after| SdkError::RateLimited {
+            retry_after: Some(retry_after),
+        })?;
+        bucket.consume(strategy, weight);
+        if let Some(global) = global {
+            global.consume(strategy, weight);
+        }
+        if let Some(bucket) = fresh {
+            if keyed.len() >= *sweep_at {
+                keyed.retain(|(_, operation), b| !b.is_idle(strategy, self.limit_for(operation).1, now));
+                *sweep_at = (keyed.len() * 2).max(MIN_SWEEP_SIZE);
+            }
+            keyed.insert(id, bucket);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use canton_core::config::OperationRateLimit;
+
+    fn config(strategy: &str) -> RateLimiterConfig {
+        RateLimiterConfig {
+            max_requests: 100,
+            window_secs: 1,
+            strategy: strategy.into(),
+            operations: HashMap::from([
+                ("transfer".to_string(), OperationRateLimit { max_requests: 2, window_secs: 60 }),
+                ("balance".to_string(), OperationRateLimit { max_requests: 50, window_secs: 1 }),
+            ]),
+            global_max_per_sec: None,
+        }
+    }
+
+    #[test]
+    fn operations_are_limited_independently() {
+        for strategy in ["token_bucket", "fixed_window"] {
+            let limiter = RateLimiter::new(&config(strategy)).unwrap();
+            let now = Instant::now();
+            assert!(limiter.check_at("alice", "transfer", 1, now).is_ok());
+            assert!(limiter.check_at("alice", "transfer", 1, now).is_ok());
+            let err = limiter.check_at("alice", "transfer", 1, now).unwrap_err();
+            assert!(matches!(err, SdkError::RateLimited { retry_after: Some(d) } if d > Duration::ZERO));
+
+            for _ in 0..50 {
+                assert!(limiter.check_at("alice", "balance", 1, now).is_ok(), "{}", strategy);
+            }
+            assert!(limiter.check_at("alice", "balance", 1, now).is_err());
+            // Unconfigured operations use the default bucket; other keys are unaffected.
+            assert!(limiter.check_at("alice", "list_parties", 1, now).is_ok());
+            assert!(limiter.check_at("bob", "transfer", 1, now).is_ok());
+        }
+    }
+
+    #[test]
+    fn token_bucket_refills_over_window() {
+        let limiter = RateLimiter::new(&config("token_bucket")).unwrap();
+        let start = Instant::now();
+        for _ in 0..2 {
+            limiter.check_at("alice", "transfer", 1, start).unwrap();
+        }
+        assert!(limiter.check_at("alice", "transfer", 1, start + Duration::from_secs(10)).is_err());
+        assert!(limiter.check_at("alice", "transfer", 1, start + Duration::from_secs(31)).is_ok());
+    }
+
+    #[test]
+    fn global_ceiling_applies_across_keys() {
+        let mut config = config("fixed_window");
+        config.global_max_per_sec = Some(10);
+        let limiter = RateLimiter::new(&config).unwrap();
+        let now = Instant::now();
+        for party in 0..10 {
+            limiter.check_at(&format!("party-{}", party), "balance", 1, now).unwrap();
+        }
+        // Each key has used 1 of its 50, but the aggregate is at the ceiling.
+        assert!(matches!(
+            limiter.check_at("party-10", "balance", 1, now),
+            Err(SdkError::RateLimited { .. })
+        ));
+        assert!(limiter.check_at("party-10", "balance", 1, now + Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn weighted_requests_consume_proportional_budget() {
+        for strategy in ["token_bucket", "fixed_window"] {
+            let mut config = config(strategy);
+            config.max_requests = 10;
+            config.window_secs = 60;
+            let limiter = RateLimiter::new(&config).unwrap();
+            let now = Instant::now();
+            limiter.check_at("alice", "submit", 5, now).unwrap();
+            for _ in 0..5 {
+                limiter.check_at("bob", "submit", 1, now).unwrap();
+            }
+            // Both keys have five units left.
+            assert!(limiter.check_at("alice", "submit", 6, now).is_err());
+            assert!(limiter.check_at("bob", "submit", 6, now).is_err());
+            limiter.check_at("alice", "submit", 5, now).unwrap();
+            limiter.check_at("bob", "submit", 5, now).unwrap();
+        }
+        let limiter = RateLimiter::new(&config("token_bucket")).unwrap();
+        assert!(matches!(
+            limiter.check_limit_weighted("alice", "transfer", 3),
+            Err(SdkError::Validation { .. })
+        ));
+    }
+
+    #[test]
+    fn idle_buckets_are_evicted_and_rejections_are_not_stored() {
+        for strategy in ["token_bucket", "fixed_window"] {
+            let mut config = config(strategy);
+            config.max_requests = 10_000;
+            config.global_max_per_sec = Some(5_000);
+            let limiter = RateLimiter::new(&config).unwrap();
+            let keyed = || limiter.state.lock().unwrap().keyed.len();
+            let start = Instant::now();
+            for party in 1..MIN_SWEEP_SIZE {
+                limiter.check_at(&format!("party-{}", party), "balance", 1, start).unwrap();
+            }
+            limiter.check_at("alice", "transfer", 1, start).unwrap();
+            assert_eq!(keyed(), MIN_SWEEP_SIZE);
+
+            // Two seconds on, every "balance" bucket has refilled; alice's minute-long
+            // "transfer" window has not, so only hers survives the sweep.
+            let later = start + Duration::from_secs(2);
+            limiter.check_at("bob", "balance", 1, later).unwrap();
+            assert_eq!(keyed(), 2, "{}", strategy);
+            assert!(limiter.check_at("alice", "transfer", 1, later).is_ok());
+            assert!(limiter.check_at("alice", "transfer", 1, later).is_err());
+
+            // Over the global ceiling: the new key is rejected and leaves no bucket behind.
+            for _ in 0..5_000 - 2 {
+                limiter.check_at("carol", "list_parties", 1, later).unwrap();
+            }
+            assert!(limiter.check_at("dave", "balance", 1, later).is_err());
+            assert_eq!(keyed(), 3, "{}", strategy);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        let mut bad = config("leaky_bucket");
+        assert!(matches!(RateLimiter::new(&bad), Err(SdkError::Config(_))));
+        bad.strategy = String::new();
+        bad.window_secs = 0;
+        assert!(matches!(RateLimiter::new(&bad), Err(SdkError::Config(_))));
+    }
+}
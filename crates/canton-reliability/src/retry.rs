@@ -0,0 +1,185 @@
+//! Retry with exponential backoff, driven by [`RetryConfig`].
+//! See research/07-production-ready-patterns.md §2, research/08.
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use canton_core::config::RetryConfig;
+use canton_core::error::{SdkError, SdkResult};
+
+type RetryPredicate = Arc<dyn Fn(&SdkError) -> bool + Send + Sync>;
+
+/// Retries an operation while its error is retryable, up to `max_attempts` attempts in total.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    config: RetryConfig,
+    retry_on: RetryPredicate,
+}
+
+impl RetryPolicy {
+    /// Retries errors for which [`SdkError::is_retryable`] holds.
+    pub fn new(config: RetryConfig) -> Self {
+        Self {
+            config,
+            retry_on: Arc::new(SdkError::is_retryable),
+        }
+    }
+
+    /// Replace the default retry predicate, e.g. to never retry authorization failures or to
+    /// retry only connection errors.
+    pub fn retry_on(mut self, predicate: impl Fn(&SdkError) -> bool + Send + Sync + 'static) -> Self {
+        self.retry_on = Arc::new(predicate);
+        self
+    }
+
+    pub fn config(&self) -> &RetryConfig {
+        &self.config
+    }
+
+    /// Run `operation`, retrying after a backoff while it fails with a retryable error.
+    /// Returns the first success or the last error. With `total_deadline_ms`, no retry is
+    /// started whose backoff would end past the deadline.
+    pub async fn execute<T, F, Fut>(&self, mut operation: F) -> SdkResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = SdkResult<T>>,
+    {
+        let start = Instant::now();
+        let deadline = self.config.total_deadline_ms.map(Duration::from_millis);
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < self.config.max_attempts && (self.retry_on)(&e) => {
+                    let backoff = self.backoff(attempt);
+                    if deadline.is_some_and(|d| start.elapsed() + backoff > d) {
+                        return Err(e);
+                    }
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Delay after the `attempt`-th failure: `initial_delay * multiplier^(attempt - 1)`, capped
+    /// at `max_delay`; with jitter, a random point in the upper half of that delay.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let multiplier = if self.config.multiplier >= 1.0 { self.config.multiplier } else { 1.0 };
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay_ms = (self.config.initial_delay_ms as f64 * multiplier.powi(exponent))
+            .min(self.config.max_delay_ms as f64);
+        let delay = Duration::from_millis(delay_ms as u64);
+        if self.config.jitter {
+            delay / 2 + delay.mul_f64(random_unit() / 2.0)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Uniform-ish value in `[0, 1)` from the std hasher's random keys; good enough for jitter.
+fn random_unit() -> f64 {
+    let bits = RandomState::new().hash_one(std::time::Instant::now());
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn config(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            initial_delay_ms: 1,
+            max_delay_ms: 4,
+            multiplier: 2.0,
+            jitter: false,
+            total_deadline_ms: None,
+        }
+    }
+
+    fn connection_error() -> SdkError {
+        SdkError::Connection {
+            message: "refused".into(),
+            cause: None,
+            backtrace: std::backtrace::Backtrace::capture(),
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_until_success_within_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let result = RetryPolicy::new(config(3))
+            .execute(|| async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err(connection_error()),
+                    n => Ok(n),
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 2);
+
+        calls.store(0, Ordering::SeqCst);
+        let result: SdkResult<()> = RetryPolicy::new(config(2))
+            .execute(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(connection_error())
+            })
+            .await;
+        assert!(matches!(result, Err(SdkError::Connection { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn predicate_controls_which_errors_retry() {
+        let policy = RetryPolicy::new(config(5)).retry_on(|e| matches!(e, SdkError::Connection { .. }));
+        let calls = AtomicU32::new(0);
+        let result: SdkResult<()> = policy
+            .execute(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(SdkError::Validation {
+                    field: "amount".into(),
+                    message: "negative".into(),
+                })
+            })
+            .await;
+        assert!(matches!(result, Err(SdkError::Validation { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn total_deadline_stops_retrying_early() {
+        let policy = RetryPolicy::new(RetryConfig {
+            initial_delay_ms: 40,
+            max_delay_ms: 1000,
+            total_deadline_ms: Some(100),
+            ..config(10)
+        });
+        let calls = AtomicU32::new(0);
+        let result: SdkResult<()> = policy
+            .execute(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(connection_error())
+            })
+            .await;
+        assert!(result.is_err());
+        // Backoffs of 40ms and 80ms: the second would end past the 100ms budget.
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn backoff_grows_and_caps() {
+        let policy = RetryPolicy::new(config(5));
+        let delays: Vec<u64> = (1..=4).map(|n| policy.backoff(n).as_millis() as u64).collect();
+        assert_eq!(delays, vec![1, 2, 4, 4]);
+
+        let jittered = RetryPolicy::new(RetryConfig { jitter: true, ..config(5) }).backoff(3);
+        assert!(jittered >= Duration::from_millis(2) && jittered <= Duration::from_millis(4));
+    }
+}
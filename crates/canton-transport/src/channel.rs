@@ -0,0 +1,37 @@
+//! gRPC channel construction from [`CantonConfig`].
+//! See research/05-grpc-protobuf-rust.md, research/08 §2.
+
+use canton_core::config::CantonConfig;
+use canton_core::error::{SdkError, SdkResult};
+use tonic::transport::{Channel, Endpoint};
+
+use crate::tls::client_tls_config;
+
+/// Build an endpoint with the configured timeouts, keep-alive and (m)TLS settings.
+pub fn endpoint(config: &CantonConfig) -> SdkResult<Endpoint> {
+    config.validate()?;
+    let mut endpoint = Endpoint::from_shared(config.endpoint.clone())
+        .map_err(|e| SdkError::Config(format!("invalid endpoint {:?}: {}", config.endpoint, e)))?
+        .connect_timeout(config.connect_timeout())
+        .timeout(config.request_timeout())
+        .http2_keep_alive_interval(config.keep_alive_interval())
+        .keep_alive_while_idle(true);
+    if let Some(ref tls) = config.tls {
+        endpoint = endpoint
+            .tls_config(client_tls_config(tls)?)
+            .map_err(|e| SdkError::Config(format!("invalid TLS config: {}", e)))?;
+    }
+    Ok(endpoint)
+}
+
+/// Connect eagerly to the configured endpoint.
+pub async fn connect(config: &CantonConfig) -> SdkResult<Channel> {
+    endpoint(config)?
+        .connect()
+        .await
+        .map_err(|e| SdkError::Connection {
+            message: format!("failed to connect to {}: {}", config.endpoint, e),
+            cause: Some(Box::new(e)),
+            backtrace: std::backtrace::Backtrace::capture(),
+        })
+}
@@ -1,4 +1,12 @@
-//! Canton Transport — gRPC channel and TLS (stub).
+//! Canton Transport — gRPC channels, TLS and connection pooling.
 
-// TODO: ChannelBuilder, ConnectionPool, interceptors
+// TODO: interceptors
 // See research/05, 08
+
+pub mod channel;
+pub mod pool;
+pub mod tls;
+
+pub use channel::{connect, endpoint};
+pub use pool::{ChannelFactory, ConnectionFactory, ConnectionPool, PooledConnection};
+pub use tls::client_tls_config;
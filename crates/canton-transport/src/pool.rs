@@ -0,0 +1,324 @@
+//! Connection pool over a pluggable [`ConnectionFactory`] (gRPC channels by default).
+//! See research/05-grpc-protobuf-rust.md, research/08 §2.
+
+use std::future::Future;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use canton_core::config::{CantonConfig, ConnectionPoolConfig};
+use canton_core::error::{SdkError, SdkResult};
+use tokio::sync::{Notify, Semaphore, SemaphorePermit};
+use tonic::transport::{Channel, Endpoint};
+
+/// Creates and health-checks pooled connections.
+#[async_trait]
+pub trait ConnectionFactory: Send + Sync {
+    type Connection: Send + Sync;
+
+    async fn connect(&self) -> SdkResult<Self::Connection>;
+
+    /// Cheap liveness check used to discard dead idle connections.
+    fn is_healthy(&self, _connection: &Self::Connection) -> bool {
+        true
+    }
+}
+
+/// Factory producing tonic channels for one endpoint.
+pub struct ChannelFactory {
+    endpoint: Endpoint,
+}
+
+impl ChannelFactory {
+    pub fn new(endpoint: Endpoint) -> Self {
+        Self { endpoint }
+    }
+
+    pub fn from_config(config: &CantonConfig) -> SdkResult<Self> {
+        Ok(Self::new(crate::channel::endpoint(config)?))
+    }
+}
+
+#[async_trait]
+impl ConnectionFactory for ChannelFactory {
+    type Connection = Channel;
+
+    async fn connect(&self) -> SdkResult<Channel> {
+        self.endpoint.connect().await.map_err(|e| SdkError::Connection {
+            message: format!("failed to connect to {}: {}", self.endpoint.uri(), e),
+            cause: Some(Box::new(e)),
+            backtrace: std::backtrace::Backtrace::capture(),
+        })
+    }
+}
+
+/// Bounded pool: at most `max_size` connections are checked out at once, and up to
+/// `min_idle` are kept ready (pre-established when `eager_init` is set).
+pub struct ConnectionPool<F: ConnectionFactory> {
+    factory: F,
+    config: ConnectionPoolConfig,
+    idle: Mutex<Vec<F::Connection>>,
+    permits: Semaphore,
+    checked_out: AtomicUsize,
+    returned: Notify,
+}
+
+impl<F: ConnectionFactory> ConnectionPool<F> {
+    pub async fn new(factory: F, config: ConnectionPoolConfig) -> SdkResult<Self> {
+        config.validate()?;
+        let pool = Self {
+            factory,
+            permits: Semaphore::new(config.max_size),
+            idle: Mutex::new(Vec::with_capacity(config.max_size)),
+            checked_out: AtomicUsize::new(0),
+            returned: Notify::new(),
+            config,
+        };
+        if pool.config.eager_init {
+            pool.maintain().await?;
+        }
+        Ok(pool)
+    }
+
+    pub fn config(&self) -> &ConnectionPoolConfig {
+        &self.config
+    }
+
+    /// Check out a connection, reusing a healthy idle one or creating a new one.
+    /// Waits while `max_size` connections are in use.
+    pub async fn acquire(&self) -> SdkResult<PooledConnection<'_, F>> {
+        let permit = self.permits.acquire().await.map_err(|_| SdkError::Connection {
+            message: "connection pool is closed".into(),
+            cause: None,
+            backtrace: std::backtrace::Backtrace::capture(),
+        })?;
+        let connection = loop {
+            let candidate = self.lock_idle().pop();
+            match candidate {
+                Some(c) if self.factory.is_healthy(&c) => break c,
+                Some(_) => continue,
+                None => break self.factory.connect().await?,
+            }
+        };
+        self.checked_out.fetch_add(1, Ordering::SeqCst);
+        Ok(PooledConnection {
+            pool: self,
+            connection: Some(connection),
+            _permit: permit,
+        })
+    }
+
+    /// Run `call` on a pooled connection. With a `deadline`, acquisition plus the call must
+    /// finish in time or the call is abandoned with [`SdkError::Timeout`]; `None` waits indefinitely
+    /// (the channel's own request timeout still applies).
+    pub async fn execute<'a, T, Fut>(
+        &'a self,
+        deadline: Option<Duration>,
+        call: impl FnOnce(PooledConnection<'a, F>) -> Fut,
+    ) -> SdkResult<T>
+    where
+        Fut: Future<Output = SdkResult<T>>,
+    {
+        let run = async {
+            let connection = self.acquire().await?;
+            call(connection).await
+        };
+        match deadline {
+            Some(duration) => tokio::time::timeout(duration, run)
+                .await
+                .map_err(|_| SdkError::Timeout {
+                    duration,
+                    operation: "pooled request".into(),
+                })?,
+            None => run.await,
+        }
+    }
+
+    /// Drop dead idle connections and top the idle set back up to `min_idle`.
+    pub async fn maintain(&self) -> SdkResult<()> {
+        if self.is_closed() {
+            return Ok(());
+        }
+        self.lock_idle().retain(|c| self.factory.is_healthy(c));
+        while self.idle_count() < self.config.min_idle {
+            let connection = self.factory.connect().await?;
+            self.lock_idle().push(connection);
+        }
+        Ok(())
+    }
+
+    /// Shut the pool down in two phases: stop handing out connections (pending and future
+    /// `acquire` calls fail), then wait up to `drain_timeout` for checked-out connections to
+    /// come back. Idle connections are dropped; connections still out when the timeout expires
+    /// are discarded on return instead of being pooled. Returns how many were force-closed.
+    pub async fn close(&self, drain_timeout: Duration) -> usize {
+        self.permits.close();
+        let drain = async {
+            loop {
+                let returned = self.returned.notified();
+                if self.checked_out.load(Ordering::SeqCst) == 0 {
+                    break;
+                }
+                returned.await;
+            }
+        };
+        let drained = tokio::time::timeout(drain_timeout, drain).await.is_ok();
+        self.lock_idle().clear();
+        if drained {
+            0
+        } else {
+            let forced = self.checked_out.load(Ordering::SeqCst);
+            tracing::warn!(forced, "connection pool drain timed out; force-closing connections");
+            forced
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.permits.is_closed()
+    }
+
+    pub fn idle_count(&self) -> usize {
+        self.lock_idle().len()
+    }
+
+    pub fn in_use(&self) -> usize {
+        self.config.max_size - self.permits.available_permits()
+    }
+
+    fn release(&self, connection: F::Connection) {
+        if !self.is_closed() && self.factory.is_healthy(&connection) {
+            let mut idle = self.lock_idle();
+            if idle.len() < self.config.max_size {
+                idle.push(connection);
+            }
+        }
+        self.checked_out.fetch_sub(1, Ordering::SeqCst);
+        self.returned.notify_waiters();
+    }
+
+    fn lock_idle(&self) -> MutexGuard<'_, Vec<F::Connection>> {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Checked-out connection; returned to the pool on drop.
+pub struct PooledConnection<'a, F: ConnectionFactory> {
+    pool: &'a ConnectionPool<F>,
+    connection: Option<F::Connection>,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl<F: ConnectionFactory> Deref for PooledConnection<'_, F> {
+    type Target = F::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.connection.as_ref().expect("connection present until drop")
+    }
+}
+
+impl<F: ConnectionFactory> Drop for PooledConnection<'_, F> {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.release(connection);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CountingFactory {
+        created: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ConnectionFactory for CountingFactory {
+        type Connection = usize;
+
+        async fn connect(&self) -> SdkResult<usize> {
+            Ok(self.created.fetch_add(1, Ordering::SeqCst))
+        }
+    }
+
+    #[tokio::test]
+    async fn eager_init_establishes_min_idle_before_acquire() {
+        let config = ConnectionPoolConfig {
+            max_size: 5,
+            min_idle: 3,
+            eager_init: true,
+        };
+        let pool = ConnectionPool::new(CountingFactory { created: AtomicUsize::new(0) }, config)
+            .await
+            .unwrap();
+        assert_eq!(pool.idle_count(), 3);
+        assert_eq!(pool.factory.created.load(Ordering::SeqCst), 3);
+
+        let conn = pool.acquire().await.unwrap();
+        assert_eq!(pool.idle_count(), 2);
+        assert_eq!(pool.factory.created.load(Ordering::SeqCst), 3);
+        drop(conn);
+        assert_eq!(pool.idle_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn execute_times_out_past_deadline() {
+        let pool = ConnectionPool::new(
+            CountingFactory { created: AtomicUsize::new(0) },
+            ConnectionPoolConfig::default(),
+        )
+        .await
+        .unwrap();
+        let result = pool
+            .execute(Some(Duration::from_millis(20)), |conn| async move {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                Ok(*conn)
+            })
+            .await;
+        assert!(matches!(result, Err(SdkError::Timeout { duration, .. }) if duration == Duration::from_millis(20)));
+        // The abandoned call's connection went back to the pool.
+        assert_eq!(pool.in_use(), 0);
+
+        let value = pool
+            .execute(Some(Duration::from_secs(5)), |conn| async move { Ok(*conn) })
+            .await
+            .unwrap();
+        assert_eq!(value, 0);
+    }
+
+    #[tokio::test]
+    async fn close_waits_for_in_flight_connection() {
+        let pool = ConnectionPool::new(
+            CountingFactory { created: AtomicUsize::new(0) },
+            ConnectionPoolConfig::default(),
+        )
+        .await
+        .unwrap();
+        let conn = pool.acquire().await.unwrap();
+
+        let (forced, _) = tokio::join!(pool.close(Duration::from_secs(5)), async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(conn);
+        });
+        assert_eq!(forced, 0);
+        assert_eq!(pool.idle_count(), 0);
+        assert!(pool.is_closed());
+        assert!(pool.acquire().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn close_reports_connections_still_out_after_timeout() {
+        let pool = ConnectionPool::new(
+            CountingFactory { created: AtomicUsize::new(0) },
+            ConnectionPoolConfig::default(),
+        )
+        .await
+        .unwrap();
+        let conn = pool.acquire().await.unwrap();
+        assert_eq!(pool.close(Duration::from_millis(10)).await, 1);
+        drop(conn);
+        assert_eq!(pool.idle_count(), 0);
+    }
+}
@@ -0,0 +1,62 @@
+//! TLS configuration for gRPC channels, including mutual TLS client identities.
+//! See research/05-grpc-protobuf-rust.md.
+
+use std::path::Path;
+
+use canton_core::config::TlsConfig;
+use canton_core::error::{SdkError, SdkResult};
+use tonic::transport::{Certificate, ClientTlsConfig, Identity};
+
+/// Build a tonic TLS config. Uses the configured CA bundle if set, webpki roots otherwise;
+/// presents a client certificate when both `client_cert_path` and `client_key_path` are set.
+pub fn client_tls_config(config: &TlsConfig) -> SdkResult<ClientTlsConfig> {
+    config.validate()?;
+    let mut tls = ClientTlsConfig::new();
+    tls = match &config.ca_cert_path {
+        Some(path) => tls.ca_certificate(Certificate::from_pem(read_pem(path)?)),
+        None => tls.with_webpki_roots(),
+    };
+    if let (Some(cert_path), Some(key_path)) = (&config.client_cert_path, &config.client_key_path) {
+        tls = tls.identity(Identity::from_pem(read_pem(cert_path)?, read_pem(key_path)?));
+    }
+    Ok(tls)
+}
+
+fn read_pem(path: impl AsRef<Path>) -> SdkResult<Vec<u8>> {
+    let path = path.as_ref();
+    std::fs::read(path)
+        .map_err(|e| SdkError::Config(format!("failed to read {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_cert_without_key_is_config_error() {
+        let config = TlsConfig {
+            ca_cert_path: None,
+            client_cert_path: Some("client.pem".into()),
+            client_key_path: None,
+        };
+        assert!(matches!(client_tls_config(&config), Err(SdkError::Config(_))));
+
+        let config = TlsConfig {
+            ca_cert_path: None,
+            client_cert_path: None,
+            client_key_path: Some("client.key".into()),
+        };
+        assert!(matches!(client_tls_config(&config), Err(SdkError::Config(_))));
+    }
+
+    #[test]
+    fn missing_pem_file_is_config_error() {
+        let config = TlsConfig {
+            ca_cert_path: None,
+            client_cert_path: Some("/nonexistent/client.pem".into()),
+            client_key_path: Some("/nonexistent/client.key".into()),
+        };
+        assert!(config.is_mutual());
+        assert!(matches!(client_tls_config(&config), Err(SdkError::Config(_))));
+    }
+}
@@ -6,6 +6,7 @@ use std::fmt;
 use std::str::FromStr;
 
 use canton_crypto::keystore::KeyFingerprint;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
 // -----------------------------------------------------------------------------
@@ -19,9 +20,34 @@ pub enum NetworkId {
     Ethereum,
     Cosmos,
     Substrate,
+    /// Сеть вне списка. Имя, совпадающее со встроенной сетью без учёта регистра (включая
+    /// алиас `evm`), при разборе строки или serde превращается во встроенную сеть, поэтому
+    /// создавайте через [`NetworkId::custom`], который такие имена отклоняет.
     Custom(String),
 }
 
+impl NetworkId {
+    /// `Custom` с проверкой: имя не должно совпадать со встроенной сетью или её алиасом,
+    /// иначе оно не переживёт сериализацию.
+    pub fn custom(name: impl Into<String>) -> Result<Self, DerivationError> {
+        let name = name.into();
+        match name.parse::<NetworkId>() {
+            Ok(NetworkId::Custom(_)) => Ok(NetworkId::Custom(name)),
+            _ => Err(DerivationError::ReservedNetworkName(name)),
+        }
+    }
+
+    /// Все известные сети (без `Custom`).
+    pub fn all() -> [NetworkId; 4] {
+        [
+            NetworkId::Canton,
+            NetworkId::Ethereum,
+            NetworkId::Cosmos,
+            NetworkId::Substrate,
+        ]
+    }
+}
+
 impl fmt::Display for NetworkId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -48,6 +74,20 @@ impl FromStr for NetworkId {
     }
 }
 
+/// Сериализуется строкой из `Display` (`"ethereum"`, `"cosmos"`, ...), как ключ в конфиге.
+impl Serialize for NetworkId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for NetworkId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(s.parse().unwrap_or_else(|e: std::convert::Infallible| match e {}))
+    }
+}
+
 // -----------------------------------------------------------------------------
 // DerivationPath
 // -----------------------------------------------------------------------------
@@ -120,6 +160,8 @@ pub enum DerivationError {
     UnsupportedNetwork(String),
     #[error("key derivation failed: {0}")]
     DerivationFailed(String),
+    #[error("network name {0:?} is reserved for a built-in network")]
+    ReservedNetworkName(String),
 }
 
 // -----------------------------------------------------------------------------
@@ -200,3 +242,36 @@ impl DerivationStrategy {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_id_round_trips_through_string_and_serde() {
+        let mut networks = NetworkId::all().to_vec();
+        networks.push(NetworkId::Custom("osmosis-1".into()));
+        for network in networks {
+            assert_eq!(network.to_string().parse::<NetworkId>().unwrap(), network);
+            let json = serde_json::to_string(&network).unwrap();
+            assert_eq!(json, format!("\"{}\"", network));
+            assert_eq!(serde_json::from_str::<NetworkId>(&json).unwrap(), network);
+        }
+        assert_eq!(serde_json::from_str::<NetworkId>("\"EVM\"").unwrap(), NetworkId::Ethereum);
+    }
+
+    #[test]
+    fn custom_network_rejects_reserved_names() {
+        assert_eq!(NetworkId::custom("osmosis-1").unwrap(), NetworkId::Custom("osmosis-1".into()));
+        for reserved in ["ethereum", "EVM", "Canton", "cosmos", "substrate"] {
+            assert!(
+                matches!(NetworkId::custom(reserved), Err(DerivationError::ReservedNetworkName(_))),
+                "{}",
+                reserved
+            );
+        }
+        // Built directly, a reserved name is aliased to the built-in network on the way back.
+        let json = serde_json::to_string(&NetworkId::Custom("EVM".into())).unwrap();
+        assert_eq!(serde_json::from_str::<NetworkId>(&json).unwrap(), NetworkId::Ethereum);
+    }
+}
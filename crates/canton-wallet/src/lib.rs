@@ -2,13 +2,17 @@
 //! See research/09, research/10-flexible-key-derivation.md.
 
 pub mod derivation;
+pub mod multisig;
 pub mod party_id;
+pub mod signing;
 pub mod wallet;
 
 pub use derivation::{
     DerivationError, DerivationPath, DerivationStrategy, IdentitySource, NetworkId,
 };
+pub use multisig::{MultiSigBuilder, MultiSignedCommands};
 pub use party_id::{canton_party_id, canton_party_id_from_fingerprint};
+pub use signing::{command_signing_payload, SignedCommands, SigningDomain};
 pub use wallet::{
-    MultiIdentityWallet, NetworkIdentity, Wallet, WalletBuilder,
+    MultiIdentityWallet, NetworkIdentity, Wallet, WalletBuilder, WatchOnlyWallet,
 };
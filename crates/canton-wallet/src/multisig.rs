@@ -0,0 +1,179 @@
+//! Multi-signature assembly: collect signatures from several keys over one command submission
+//! until a threshold is met. See research/06-cryptographic-requirements.md §3.
+
+use std::collections::HashMap;
+
+use canton_core::types::Commands;
+use canton_core::{SdkError, SdkResult};
+use canton_crypto::keystore::{KeyFingerprint, KeyStore};
+use canton_crypto::Signature;
+
+use crate::signing::command_signing_payload;
+
+/// Collects `threshold`-of-`signers` signatures over the canonical bytes of `commands`
+/// (see [`command_signing_payload`]).
+pub struct MultiSigBuilder {
+    commands: Commands,
+    payload: Vec<u8>,
+    signers: Vec<KeyFingerprint>,
+    threshold: usize,
+    signatures: HashMap<KeyFingerprint, Signature>,
+}
+
+/// Submission carrying at least `threshold` signatures, in signer order.
+#[derive(Debug, Clone)]
+pub struct MultiSignedCommands {
+    pub commands: Commands,
+    pub signatures: Vec<(KeyFingerprint, Signature)>,
+}
+
+impl MultiSigBuilder {
+    pub fn new(commands: Commands, signers: Vec<KeyFingerprint>, threshold: usize) -> SdkResult<Self> {
+        if threshold == 0 || threshold > signers.len() {
+            return Err(SdkError::Validation {
+                field: "threshold".into(),
+                message: format!("threshold {} must be between 1 and {} signers", threshold, signers.len()),
+            });
+        }
+        if signers.iter().enumerate().any(|(i, s)| signers[..i].contains(s)) {
+            return Err(SdkError::Validation {
+                field: "signers".into(),
+                message: "duplicate signer".into(),
+            });
+        }
+        let payload = command_signing_payload(&commands);
+        Ok(Self {
+            commands,
+            payload,
+            signers,
+            threshold,
+            signatures: HashMap::new(),
+        })
+    }
+
+    /// Bytes each signer signs.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Sign with `signer`'s key held in `keystore`. Signing again with the same key replaces
+    /// its signature.
+    pub async fn sign_with(&mut self, keystore: &dyn KeyStore, signer: &KeyFingerprint) -> SdkResult<()> {
+        if !self.signers.contains(signer) {
+            return Err(SdkError::Validation {
+                field: "signer".into(),
+                message: format!("{} is not a signer of this submission", signer.to_hex()),
+            });
+        }
+        let signature = keystore
+            .sign(signer, &self.payload)
+            .await
+            .map_err(|e| SdkError::Crypto(e.to_string()))?;
+        self.signatures.insert(signer.clone(), signature);
+        Ok(())
+    }
+
+    pub fn signed_by(&self) -> Vec<&KeyFingerprint> {
+        self.signers.iter().filter(|s| self.signatures.contains_key(s)).collect()
+    }
+
+    pub fn pending(&self) -> Vec<&KeyFingerprint> {
+        self.signers.iter().filter(|s| !self.signatures.contains_key(s)).collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.signatures.len() >= self.threshold
+    }
+
+    /// The signed submission, once the threshold is met.
+    pub fn finalize(mut self) -> SdkResult<MultiSignedCommands> {
+        if !self.is_complete() {
+            return Err(SdkError::Validation {
+                field: "signatures".into(),
+                message: format!("{} of {} required signatures collected", self.signatures.len(), self.threshold),
+            });
+        }
+        let signatures = self
+            .signers
+            .iter()
+            .filter_map(|s| self.signatures.remove(s).map(|sig| (s.clone(), sig)))
+            .collect();
+        Ok(MultiSignedCommands {
+            commands: self.commands,
+            signatures,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use canton_core::types::{Command, CreateCommand, DamlRecord, Identifier};
+    use canton_crypto::keystore::KeyMetadata;
+    use canton_crypto::{InMemoryKeyStore, KeyAlgorithm, KeyPurpose};
+
+    use super::*;
+
+    fn commands() -> Commands {
+        Commands {
+            ledger_id: None,
+            workflow_id: String::new(),
+            application_id: "treasury".into(),
+            command_id: "payout-1".into(),
+            act_as: vec!["treasury::1220ab".into()],
+            read_as: Vec::new(),
+            commands: vec![Command::Create(CreateCommand {
+                template_id: Identifier::new("pkg", "Treasury", "Payout"),
+                create_arguments: DamlRecord::new().field("amount", "1000000"),
+            })],
+            min_ledger_time_abs: None,
+            min_ledger_time_rel: None,
+            deduplication_period: None,
+            submission_id: None,
+            disclosed_contracts: Vec::new(),
+        }
+    }
+
+    async fn signer() -> (InMemoryKeyStore, KeyFingerprint) {
+        let keystore = InMemoryKeyStore::new();
+        let metadata = KeyMetadata {
+            name: None,
+            description: None,
+            tags: HashMap::new(),
+            created_at: chrono::Utc::now(),
+            expires_at: None,
+        };
+        let fingerprint = keystore
+            .generate_key(KeyAlgorithm::Ed25519, KeyPurpose::Signing, metadata)
+            .await
+            .unwrap();
+        (keystore, fingerprint)
+    }
+
+    #[tokio::test]
+    async fn finalizable_only_at_threshold() {
+        let (ks_a, a) = signer().await;
+        let (_ks_b, b) = signer().await;
+        let (ks_c, c) = signer().await;
+        let mut builder = MultiSigBuilder::new(commands(), vec![a.clone(), b.clone(), c.clone()], 2).unwrap();
+
+        builder.sign_with(&ks_a, &a).await.unwrap();
+        assert!(!builder.is_complete());
+        assert_eq!(builder.pending(), vec![&b, &c]);
+        // A key that is not one of the signers is refused.
+        assert!(builder.sign_with(&ks_a, &b).await.is_err());
+
+        builder.sign_with(&ks_c, &c).await.unwrap();
+        assert!(builder.is_complete());
+        assert_eq!(builder.signed_by(), vec![&a, &c]);
+
+        let payload = builder.payload().to_vec();
+        let signed = builder.finalize().unwrap();
+        assert_eq!(signed.signatures.len(), 2);
+        let (fp, signature) = &signed.signatures[1];
+        assert_eq!(fp, &c);
+        assert!(ks_c.verify(&c, &payload, signature).await.unwrap());
+        assert!(MultiSigBuilder::new(commands(), vec![a, b], 3).is_err());
+    }
+}
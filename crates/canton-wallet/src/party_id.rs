@@ -14,3 +14,15 @@ pub fn canton_party_id_from_fingerprint(party_hint: &str, fingerprint: &KeyFinge
     let hex = fingerprint.0.iter().map(|b| format!("{:02x}", b)).collect::<String>();
     PartyId::new_unchecked(format!("{}::{}", party_hint, hex))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_party_id_is_structured() {
+        let party = canton_party_id_from_fingerprint("wallet", &KeyFingerprint([0xab; 32]));
+        assert_eq!(party.hint(), Some("wallet"));
+        assert_eq!(party.fingerprint(), Some("ab".repeat(32).as_str()));
+    }
+}
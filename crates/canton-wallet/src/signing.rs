@@ -0,0 +1,327 @@
+//! Domain-separated signing: every payload is bound to the context it was signed for, so a
+//! signature over a command cannot be replayed as, say, a login challenge response.
+//! See research/06-cryptographic-requirements.md.
+
+use std::borrow::Cow;
+
+use canton_core::types::Commands;
+use canton_core::{SdkError, SdkResult};
+use canton_crypto::keystore::{verify_signature, KeyFingerprint, KeyStore};
+use canton_crypto::{KeyAlgorithm, Signature};
+use serde::{Deserialize, Serialize};
+
+use crate::derivation::NetworkId;
+use crate::wallet::{MultiIdentityWallet, Wallet};
+
+/// Prefix of every domain-separated signing input.
+const DOMAIN_PREFIX: &[u8] = b"canton-sdk/sign/v1/";
+
+/// Context a signature is valid in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SigningDomain {
+    /// Ledger command submissions (e.g. `Commands::canonical_bytes`).
+    Command,
+    /// Authentication challenges (login, proof of key possession).
+    Challenge,
+    /// Free-form off-ledger messages.
+    Message,
+    /// Application-defined domain; tagged `custom/<name>`, so it never collides with a
+    /// built-in domain of the same name.
+    Custom(String),
+}
+
+impl SigningDomain {
+    fn tag(&self) -> Cow<'_, str> {
+        match self {
+            SigningDomain::Command => "command".into(),
+            SigningDomain::Challenge => "challenge".into(),
+            SigningDomain::Message => "message".into(),
+            SigningDomain::Custom(name) => format!("custom/{}", name).into(),
+        }
+    }
+
+    /// Bytes actually signed: prefix, length-prefixed domain tag, length-prefixed payload.
+    pub fn signing_input(&self, payload: &[u8]) -> Vec<u8> {
+        let tag = self.tag();
+        let tag = tag.as_bytes();
+        let mut out = Vec::with_capacity(DOMAIN_PREFIX.len() + 8 + tag.len() + payload.len());
+        out.extend_from_slice(DOMAIN_PREFIX);
+        out.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+        out.extend_from_slice(tag);
+        out.extend_from_slice(&(payload.len() as u64).to_be_bytes());
+        out.extend_from_slice(payload);
+        out
+    }
+}
+
+/// What gets signed for a command submission: [`Commands::canonical_bytes`] in the
+/// [`SigningDomain::Command`] domain.
+pub fn command_signing_payload(commands: &Commands) -> Vec<u8> {
+    SigningDomain::Command.signing_input(&commands.canonical_bytes())
+}
+
+/// Version of the [`SignedCommands::to_bytes`] envelope.
+const SIGNED_COMMANDS_VERSION: u32 = 1;
+
+/// Commands signed away from the submitting machine (e.g. on an air-gapped signer), carrying
+/// the signer's public key so the online side can check the signature before submitting.
+/// Moves between machines as [`SignedCommands::to_bytes`].
+#[derive(Debug, Clone)]
+pub struct SignedCommands {
+    pub commands: Commands,
+    pub signature: Signature,
+    pub public_key: Vec<u8>,
+}
+
+impl SignedCommands {
+    /// Sign `commands` with a key from `keystore` (the offline step).
+    pub async fn sign(commands: Commands, keystore: &dyn KeyStore, fingerprint: &KeyFingerprint) -> SdkResult<Self> {
+        let crypto = |e: canton_crypto::KeyStoreError| SdkError::Crypto(e.to_string());
+        let signature = keystore.sign(fingerprint, &command_signing_payload(&commands)).await.map_err(crypto)?;
+        let public_key = keystore.export_public_key(fingerprint).await.map_err(crypto)?;
+        Ok(Self {
+            commands,
+            signature,
+            public_key,
+        })
+    }
+
+    /// Fingerprint of the signing key.
+    pub fn signer(&self) -> KeyFingerprint {
+        KeyFingerprint::compute(&self.public_key, self.signature.algorithm)
+    }
+
+    /// Check that the signature covers exactly these commands; no key store needed.
+    ///
+    /// This accepts any key that is consistent with the signature, including one an attacker
+    /// substituted together with their own signature. Callers must also compare [`Self::signer`]
+    /// with the key they expect, or use [`Self::verify_signer`].
+    pub fn verify(&self) -> SdkResult<()> {
+        let payload = command_signing_payload(&self.commands);
+        match verify_signature(self.signature.algorithm, &self.public_key, &payload, &self.signature) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(SdkError::Crypto(format!(
+                "signature by {} does not match command {}",
+                self.signer().to_hex(),
+                self.commands.command_id
+            ))),
+            Err(e) => Err(SdkError::Crypto(e.to_string())),
+        }
+    }
+
+    /// [`Self::verify`], and require the signature to come from `expected`. On success the
+    /// commands are ready to submit (e.g. `LedgerClient::submit_commands`).
+    pub fn verify_signer(&self, expected: &KeyFingerprint) -> SdkResult<&Commands> {
+        let signer = self.signer();
+        if &signer != expected {
+            return Err(SdkError::Crypto(format!(
+                "command {} signed by {}, expected {}",
+                self.commands.command_id,
+                signer.to_hex(),
+                expected.to_hex()
+            )));
+        }
+        self.verify()?;
+        Ok(&self.commands)
+    }
+
+    /// Stable, versioned encoding (JSON envelope) for carrying across an air gap.
+    pub fn to_bytes(&self) -> SdkResult<Vec<u8>> {
+        let envelope = SignedCommandsEnvelope {
+            version: SIGNED_COMMANDS_VERSION,
+            commands: self.commands.clone(),
+            algorithm: algorithm_name(self.signature.algorithm).to_string(),
+            signature: hex::encode(&self.signature.bytes),
+            public_key: hex::encode(&self.public_key),
+        };
+        serde_json::to_vec(&envelope).map_err(|e| SdkError::Serialization(e.to_string()))
+    }
+
+    /// Decode output of [`Self::to_bytes`]. Does not verify; call [`Self::verify_signer`].
+    pub fn from_bytes(bytes: &[u8]) -> SdkResult<Self> {
+        let serialization = |message: String| SdkError::Serialization(format!("signed commands: {}", message));
+        let envelope: SignedCommandsEnvelope =
+            serde_json::from_slice(bytes).map_err(|e| serialization(e.to_string()))?;
+        if envelope.version != SIGNED_COMMANDS_VERSION {
+            return Err(serialization(format!("unsupported version {}", envelope.version)));
+        }
+        let algorithm = algorithm_from_name(&envelope.algorithm)
+            .ok_or_else(|| serialization(format!("unknown algorithm {}", envelope.algorithm)))?;
+        Ok(Self {
+            commands: envelope.commands,
+            signature: Signature {
+                algorithm,
+                bytes: hex::decode(&envelope.signature).map_err(|e| serialization(e.to_string()))?,
+            },
+            public_key: hex::decode(&envelope.public_key).map_err(|e| serialization(e.to_string()))?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SignedCommandsEnvelope {
+    version: u32,
+    commands: Commands,
+    algorithm: String,
+    signature: String,
+    public_key: String,
+}
+
+fn algorithm_name(algorithm: KeyAlgorithm) -> &'static str {
+    match algorithm {
+        KeyAlgorithm::Ed25519 => "ed25519",
+        KeyAlgorithm::EcdsaP256 => "ecdsa-p256",
+        KeyAlgorithm::EcdsaSecp256k1 => "ecdsa-secp256k1",
+        KeyAlgorithm::X25519 => "x25519",
+    }
+}
+
+fn algorithm_from_name(name: &str) -> Option<KeyAlgorithm> {
+    [KeyAlgorithm::Ed25519, KeyAlgorithm::EcdsaP256, KeyAlgorithm::EcdsaSecp256k1, KeyAlgorithm::X25519]
+        .into_iter()
+        .find(|algorithm| algorithm_name(*algorithm) == name)
+}
+
+impl MultiIdentityWallet {
+    /// Sign `payload` for `network` within `domain`.
+    pub async fn sign(
+        &self,
+        network: NetworkId,
+        domain: &SigningDomain,
+        payload: &[u8],
+    ) -> SdkResult<Signature> {
+        self.sign_raw(network, &domain.signing_input(payload)).await
+    }
+
+    /// Verify a signature produced by [`MultiIdentityWallet::sign`] in the same `domain`.
+    pub async fn verify(
+        &self,
+        network: NetworkId,
+        domain: &SigningDomain,
+        payload: &[u8],
+        signature: &Signature,
+    ) -> SdkResult<bool> {
+        let fingerprint = self.fingerprint_for(&network)?;
+        self.keystore()
+            .verify(fingerprint, &domain.signing_input(payload), signature)
+            .await
+            .map_err(|e| SdkError::Crypto(e.to_string()))
+    }
+
+    /// Sign `payload` as-is, without domain separation (same as `sign_for_network`).
+    pub async fn sign_raw(&self, network: NetworkId, payload: &[u8]) -> SdkResult<Signature> {
+        self.sign_for_network(network, payload).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use canton_crypto::keystore::{KeyMetadata, KeyStore};
+    use canton_crypto::{InMemoryKeyStore, KeyAlgorithm, KeyPurpose};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn signature_does_not_verify_in_another_domain() {
+        let keystore = Arc::new(InMemoryKeyStore::new());
+        let fingerprint = keystore
+            .generate_key(
+                KeyAlgorithm::Ed25519,
+                KeyPurpose::Signing,
+                KeyMetadata {
+                    name: None,
+                    description: None,
+                    tags: HashMap::new(),
+                    created_at: chrono::Utc::now(),
+                    expires_at: None,
+                },
+            )
+            .await
+            .unwrap();
+        let wallet = MultiIdentityWallet::new(
+            keystore,
+            HashMap::from([(NetworkId::Canton, fingerprint)]),
+            "alice",
+        );
+
+        let payload = b"transfer 10 to bob";
+        let signature = wallet.sign(NetworkId::Canton, &SigningDomain::Command, payload).await.unwrap();
+        assert!(wallet
+            .verify(NetworkId::Canton, &SigningDomain::Command, payload, &signature)
+            .await
+            .unwrap());
+        assert!(!wallet
+            .verify(NetworkId::Canton, &SigningDomain::Challenge, payload, &signature)
+            .await
+            .unwrap());
+
+        let custom = SigningDomain::Custom("command".into());
+        let signature = wallet.sign(NetworkId::Canton, &custom, payload).await.unwrap();
+        assert!(wallet.verify(NetworkId::Canton, &custom, payload, &signature).await.unwrap());
+        assert!(!wallet
+            .verify(NetworkId::Canton, &SigningDomain::Command, payload, &signature)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn offline_signed_commands_verify_without_keystore() {
+        use canton_core::types::{Command, CreateCommand, DamlRecord, Identifier};
+
+        let offline = InMemoryKeyStore::new();
+        let metadata = KeyMetadata {
+            name: None,
+            description: None,
+            tags: HashMap::new(),
+            created_at: chrono::Utc::now(),
+            expires_at: None,
+        };
+        let fingerprint = offline
+            .generate_key(KeyAlgorithm::EcdsaSecp256k1, KeyPurpose::Signing, metadata)
+            .await
+            .unwrap();
+        let commands = Commands {
+            ledger_id: None,
+            workflow_id: String::new(),
+            application_id: "cold-wallet".into(),
+            command_id: "cmd-1".into(),
+            act_as: vec!["alice::1220ab".into()],
+            read_as: Vec::new(),
+            commands: vec![Command::Create(CreateCommand {
+                template_id: Identifier::new("pkg", "Iou", "Iou"),
+                create_arguments: DamlRecord::new().field("amount", "10"),
+            })],
+            min_ledger_time_abs: None,
+            min_ledger_time_rel: None,
+            deduplication_period: None,
+            submission_id: None,
+            disclosed_contracts: Vec::new(),
+        };
+
+        // Offline side: sign and export. Only the bytes cross the air gap.
+        let blob = SignedCommands::sign(commands, &offline, &fingerprint)
+            .await
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+        drop(offline);
+
+        let signed = SignedCommands::from_bytes(&blob).unwrap();
+        assert_eq!(signed.signer(), fingerprint);
+        let verified = signed.verify_signer(&fingerprint).unwrap();
+        assert_eq!(verified.command_id, "cmd-1");
+        assert!(matches!(
+            signed.verify_signer(&KeyFingerprint([0; 32])),
+            Err(SdkError::Crypto(message)) if message.contains("expected")
+        ));
+
+        let mut tampered = signed.clone();
+        tampered.commands.act_as.push("mallory::1220cd".into());
+        let tampered = SignedCommands::from_bytes(&tampered.to_bytes().unwrap()).unwrap();
+        assert!(matches!(tampered.verify(), Err(SdkError::Crypto(_))));
+        assert!(matches!(SignedCommands::from_bytes(b"{}"), Err(SdkError::Serialization(_))));
+    }
+}
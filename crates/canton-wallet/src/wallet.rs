@@ -93,6 +93,16 @@ impl MultiIdentityWallet {
     fn canton_fingerprint(&self) -> Option<&KeyFingerprint> {
         self.keys_by_network.get(&NetworkId::Canton)
     }
+
+    pub(crate) fn keystore(&self) -> &Arc<dyn KeyStore> {
+        &self.keystore
+    }
+
+    pub(crate) fn fingerprint_for(&self, network: &NetworkId) -> SdkResult<&KeyFingerprint> {
+        self.keys_by_network
+            .get(network)
+            .ok_or_else(|| SdkError::Config(format!("identity not configured for network: {}", network)))
+    }
 }
 
 #[async_trait]
@@ -128,10 +138,7 @@ impl Wallet for MultiIdentityWallet {
     }
 
     async fn sign_for_network(&self, network: NetworkId, payload: &[u8]) -> SdkResult<Signature> {
-        let fp = self
-            .keys_by_network
-            .get(&network)
-            .ok_or_else(|| SdkError::Config(format!("identity not configured for network: {}", network)))?;
+        let fp = self.fingerprint_for(&network)?;
         self.keystore
             .sign(fp, payload)
             .await
@@ -143,6 +150,42 @@ impl Wallet for MultiIdentityWallet {
     }
 }
 
+// -----------------------------------------------------------------------------
+// WatchOnlyWallet — наблюдение за party без ключей
+// -----------------------------------------------------------------------------
+
+/// Кошелёк только для чтения: известен Party ID, ключей нет. Подходит для мониторинга
+/// контрактов party; любая подпись завершается ошибкой [`SdkError::WatchOnly`].
+#[derive(Debug, Clone)]
+pub struct WatchOnlyWallet {
+    party_id: PartyId,
+}
+
+impl WatchOnlyWallet {
+    pub fn new(party_id: PartyId) -> Self {
+        Self { party_id }
+    }
+}
+
+#[async_trait]
+impl Wallet for WatchOnlyWallet {
+    fn party_id_for_canton(&self) -> PartyId {
+        self.party_id.clone()
+    }
+
+    async fn sign_for_canton(&self, _payload: &[u8]) -> SdkResult<Signature> {
+        Err(SdkError::WatchOnly {
+            party: self.party_id.to_string(),
+        })
+    }
+
+    async fn sign_for_network(&self, _network: NetworkId, _payload: &[u8]) -> SdkResult<Signature> {
+        Err(SdkError::WatchOnly {
+            party: self.party_id.to_string(),
+        })
+    }
+}
+
 // -----------------------------------------------------------------------------
 // WalletBuilder — гибкая сборка из одной мнемоники или разных источников
 // -----------------------------------------------------------------------------
@@ -211,3 +254,27 @@ impl WalletBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn watch_only_wallet_exposes_party_but_cannot_sign() {
+        let party = PartyId::new("alice::1220ab").unwrap();
+        let wallet = WatchOnlyWallet::new(party.clone());
+        assert_eq!(wallet.party_id_for_canton(), party);
+        assert!(matches!(
+            wallet.identity_for_network(NetworkId::Canton),
+            Some(NetworkIdentity::Canton(p)) if p == party
+        ));
+        assert!(matches!(
+            wallet.sign_for_canton(b"payload").await,
+            Err(SdkError::WatchOnly { party: p }) if p == "alice::1220ab"
+        ));
+        assert!(matches!(
+            wallet.sign_for_network(NetworkId::Ethereum, b"payload").await,
+            Err(SdkError::WatchOnly { .. })
+        ));
+    }
+}